@@ -0,0 +1,235 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use srcpos::*;
+
+const ASCII_LINE: &str = "    let value = compute(alpha, beta) + 42; // comment\n";
+const UNICODE_LINE: &str = "    let 名前 = \"héllo wörld 🦀\"; // コメント\n";
+const SIZES: [usize; 3] = [1 << 10, 1 << 16, 1 << 20];
+
+/// Text of about `size` bytes made of the line
+fn corpus(line: &str, size: usize) -> String {
+    line.repeat(size / line.len() + 1)
+}
+
+/// Char boundary offsets spread over the text
+fn offsets(text: &str, count: usize) -> Vec<usize> {
+    let step = (text.len() / count).max(1);
+    let mut offsets: Vec<usize> = (0..text.len()).step_by(step).collect();
+    for o in offsets.iter_mut() {
+        while !text.is_char_boundary(*o) {
+            *o -= 1;
+        }
+    }
+    offsets
+}
+
+fn corpora() -> Vec<(&'static str, usize, String)> {
+    let mut corpora = Vec::new();
+    for &size in SIZES.iter() {
+        corpora.push(("ascii", size, corpus(ASCII_LINE, size)));
+        corpora.push(("unicode", size, corpus(UNICODE_LINE, size)));
+    }
+    corpora
+}
+
+fn bench_from_offset(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Pos::from_offset");
+    for (name, size, text) in corpora().into_iter().filter(|c| c.1 <= 1 << 16) {
+        let offsets = offsets(&text, 64);
+        group.throughput(Throughput::Elements(offsets.len() as u64));
+        group.bench_with_input(BenchmarkId::new(name, size), &text, |b, text| {
+            b.iter(|| {
+                for &o in offsets.iter() {
+                    black_box(Pos::from_offset(text, o));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_line_index_new(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LineIndex::new");
+    for (name, size, text) in corpora() {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::new(name, size), &text, |b, text| {
+            b.iter(|| LineIndex::new(black_box(text)))
+        });
+    }
+    group.finish();
+}
+
+/// About 10 MB of mixed lines with CRLF breaks, compare runs with and without the `fast` feature
+fn bench_line_index_new_large(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LineIndex::new large");
+    group.sample_size(20);
+    let line = [ASCII_LINE, UNICODE_LINE].concat().replace('\n', "\r\n");
+    let text = corpus(&line, 10 << 20);
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_with_input(
+        BenchmarkId::new("mixed crlf", text.len()),
+        &text,
+        |b, text| b.iter(|| LineIndex::new(black_box(text))),
+    );
+    let ascii = corpus(ASCII_LINE, 10 << 20);
+    group.throughput(Throughput::Bytes(ascii.len() as u64));
+    group.bench_with_input(BenchmarkId::new("ascii", ascii.len()), &ascii, |b, text| {
+        b.iter(|| LineIndex::new(black_box(text)))
+    });
+    group.finish();
+}
+
+/// Sequential against parallel build on about 64 MB, with the `rayon` feature
+#[cfg(feature = "rayon")]
+fn bench_line_index_new_parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LineIndex::new_parallel");
+    group.sample_size(10);
+    let text = corpus(&[ASCII_LINE, UNICODE_LINE].concat(), 64 << 20);
+    group.throughput(Throughput::Bytes(text.len() as u64));
+    group.bench_with_input(BenchmarkId::new("new", text.len()), &text, |b, text| {
+        b.iter(|| LineIndex::new(black_box(text)))
+    });
+    group.bench_with_input(
+        BenchmarkId::new("new_parallel", text.len()),
+        &text,
+        |b, text| b.iter(|| LineIndex::new_parallel(black_box(text))),
+    );
+    group.finish();
+}
+
+/// Sequential against parallel indexing of 256 files of about 256 KB, with the `rayon` feature
+#[cfg(feature = "rayon")]
+fn bench_line_index_all_parallel(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LineIndex::index_all_parallel");
+    group.sample_size(10);
+    let files: Vec<String> = (0..256)
+        .map(|_| corpus(&[ASCII_LINE, UNICODE_LINE].concat(), 256 << 10))
+        .collect();
+    let total: usize = files.iter().map(String::len).sum();
+    group.throughput(Throughput::Bytes(total as u64));
+    group.bench_with_input(BenchmarkId::new("new", total), &files, |b, files| {
+        b.iter(|| {
+            files
+                .iter()
+                .map(|t| LineIndex::new(black_box(t)))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_with_input(
+        BenchmarkId::new("index_all_parallel", total),
+        &files,
+        |b, files| b.iter(|| LineIndex::index_all_parallel(black_box(files))),
+    );
+    group.finish();
+}
+
+fn bench_line_index_pos_of(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LineIndex::pos_of");
+    for (name, size, text) in corpora() {
+        let index = LineIndex::new(&text);
+        let offsets = offsets(&text, 1024);
+        group.throughput(Throughput::Elements(offsets.len() as u64));
+        group.bench_with_input(BenchmarkId::new(name, size), &index, |b, index| {
+            b.iter(|| {
+                for &o in offsets.iter() {
+                    black_box(index.pos_of(o));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+/// Sorted token offsets, independent binary searches against one cursor
+fn bench_line_index_sorted_tokens(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LineIndex sorted tokens");
+    for (name, size, text) in corpora() {
+        let index = LineIndex::new(&text);
+        let offsets = offsets(&text, text.len() / 4);
+        group.throughput(Throughput::Elements(offsets.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new(format!("pos_of {}", name), size),
+            &index,
+            |b, index| {
+                b.iter(|| {
+                    for &o in offsets.iter() {
+                        black_box(index.pos_of(o));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new(format!("cursor {}", name), size),
+            &index,
+            |b, index| {
+                b.iter(|| {
+                    let mut cursor = index.cursor();
+                    for &o in offsets.iter() {
+                        black_box(cursor.pos_of(o));
+                    }
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+/// 100k sorted offsets, per offset lookups against one merged pass
+fn bench_positions_of_sorted(c: &mut Criterion) {
+    let mut group = c.benchmark_group("LineIndex::positions_of_sorted");
+    for (name, size, text) in corpora().into_iter().filter(|c| c.1 == 1 << 20) {
+        let index = LineIndex::new(&text);
+        let offsets = offsets(&text, 100_000);
+        group.throughput(Throughput::Elements(offsets.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new(format!("pos_of {}", name), size),
+            &index,
+            |b, index| b.iter(|| offsets.iter().map(|&o| index.pos_of(o)).collect::<Vec<_>>()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new(format!("merged {}", name), size),
+            &index,
+            |b, index| b.iter(|| index.positions_of_sorted(black_box(&offsets))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_merge_overlapping(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Loc::merge_overlapping");
+    for &count in [1 << 6, 1 << 10, 1 << 14].iter() {
+        let locs: Vec<Loc> = (0..count)
+            .map(|i| {
+                let line = (i * 7919) % count / 4;
+                let column = (i * 31) % 80;
+                Loc::new_at(line, column, line + i % 3, (column + 17) % 80)
+            })
+            .collect();
+        group.throughput(Throughput::Elements(count as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(count), &locs, |b, locs| {
+            b.iter(|| Loc::merge_overlapping(locs.iter().copied()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_from_offset,
+    bench_line_index_new,
+    bench_line_index_new_large,
+    bench_line_index_pos_of,
+    bench_line_index_sorted_tokens,
+    bench_positions_of_sorted,
+    bench_merge_overlapping
+);
+#[cfg(feature = "rayon")]
+criterion_group!(
+    parallel_benches,
+    bench_line_index_new_parallel,
+    bench_line_index_all_parallel
+);
+
+#[cfg(feature = "rayon")]
+criterion_main!(benches, parallel_benches);
+#[cfg(not(feature = "rayon"))]
+criterion_main!(benches);
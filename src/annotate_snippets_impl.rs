@@ -0,0 +1,32 @@
+//! annotate-snippets 0.9 `SourceAnnotation` ranges are char offsets from the `Slice` source start
+
+use crate::{LineIndex, Loc};
+use annotate_snippets::snippet::{AnnotationType, SourceAnnotation};
+
+impl Loc {
+    /// `SourceAnnotation::range` of the loc, the index must be of the whole `Slice` source  
+    /// `None` if not in the index
+    pub fn to_annotation_range(&self, index: &LineIndex) -> Option<(usize, usize)> {
+        let from = index.char_offset_of(self.from)?;
+        let to = index.char_offset_of(self.to)?;
+        if from <= to {
+            Some((from, to))
+        } else {
+            None
+        }
+    }
+
+    /// `SourceAnnotation` of the loc, see [`Loc::to_annotation_range`]
+    pub fn to_source_annotation<'a>(
+        &self,
+        index: &LineIndex,
+        label: &'a str,
+        annotation_type: AnnotationType,
+    ) -> Option<SourceAnnotation<'a>> {
+        Some(SourceAnnotation {
+            range: self.to_annotation_range(index)?,
+            label,
+            annotation_type,
+        })
+    }
+}
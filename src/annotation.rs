@@ -0,0 +1,79 @@
+use crate::display::snippet_last_line;
+use crate::Loc;
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::fmt::Display;
+
+/// Level of a GitHub Actions annotation
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub enum AnnotationLevel {
+    /// `::error`
+    Error,
+    /// `::warning`
+    Warning,
+    /// `::notice`
+    Notice,
+}
+
+impl Display for AnnotationLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Notice => "notice",
+        })
+    }
+}
+
+/// Percent escape a workflow command message
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Percent escape a workflow command property value
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// GitHub Actions workflow command annotating the zero based loc in file,
+/// `::error file=src/x.rs,line=3,col=7,endColumn=9::message`  
+/// Lines and columns are one based with an inclusive end column,
+/// the columns are left out for multi line locs as GitHub ignores them,
+/// an end at column 0 of a later line ends on the previous line  
+/// A [`Pos::DUMMY`](crate::Pos::DUMMY) start annotates the whole file, a [`Pos::DUMMY`](crate::Pos::DUMMY) end is the start
+pub fn annotation_command(level: AnnotationLevel, file: &str, loc: Loc, message: &str) -> String {
+    let Loc { from, to } = loc;
+    let to = if to.is_dummy() { from } else { to };
+    let position = if from.is_dummy() {
+        String::new()
+    } else if from.line != to.line {
+        format!(
+            ",line={},endLine={}",
+            from.line.saturating_add(1),
+            snippet_last_line(&loc) + 1
+        )
+    } else if from == to {
+        format!(
+            ",line={},col={}",
+            from.line.saturating_add(1),
+            from.column.saturating_add(1)
+        )
+    } else {
+        format!(
+            ",line={},col={},endColumn={}",
+            from.line.saturating_add(1),
+            from.column.saturating_add(1),
+            to.column
+        )
+    };
+    format!(
+        "::{} file={}{}::{}",
+        level,
+        escape_property(file),
+        position,
+        escape_data(message)
+    )
+}
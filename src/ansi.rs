@@ -0,0 +1,70 @@
+use crate::{Loc, Pos};
+use core::fmt;
+use core::fmt::Display;
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+impl Loc {
+    /// [`Loc::display_smart`] with bold numbers and dim separators,
+    /// plain text if not `color`, e.g. when `NO_COLOR` is set
+    #[inline]
+    pub const fn display_colored(&self, color: bool) -> ColoredDisplay {
+        ColoredDisplay { loc: *self, color }
+    }
+}
+
+impl Pos {
+    /// `line:column` with bold numbers and dim separators,
+    /// plain text if not `color`, e.g. when `NO_COLOR` is set
+    #[inline]
+    pub const fn display_colored(&self, color: bool) -> ColoredDisplay {
+        Loc::new_same_pos(*self).display_colored(color)
+    }
+}
+
+/// Display adapter, see [`Loc::display_colored`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColoredDisplay {
+    loc: Loc,
+    color: bool,
+}
+
+impl ColoredDisplay {
+    fn num(&self, f: &mut fmt::Formatter<'_>, n: usize) -> fmt::Result {
+        if self.color {
+            write!(f, "{}{}{}", BOLD, n, RESET)
+        } else {
+            write!(f, "{}", n)
+        }
+    }
+    fn sep(&self, f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+        if self.color {
+            write!(f, "{}{}{}", DIM, s, RESET)
+        } else {
+            f.write_str(s)
+        }
+    }
+}
+
+impl Display for ColoredDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Loc { from, to } = self.loc;
+        if from.is_dummy() || to.is_dummy() {
+            return write!(f, "{}", self.loc.bare());
+        }
+        self.num(f, from.line)?;
+        self.sep(f, ":")?;
+        self.num(f, from.column)?;
+        if from == to {
+            return Ok(());
+        }
+        self.sep(f, "-")?;
+        if from.line != to.line {
+            self.num(f, to.line)?;
+            self.sep(f, ":")?;
+        }
+        self.num(f, to.column)
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{Loc, Pos};
+use core::fmt;
+
+/// Names of the differing coordinates
+struct Diff<'a>(&'a [(&'static str, usize, usize)]);
+
+impl fmt::Display for Diff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for &(name, a, b) in self.0 {
+            if a != b {
+                if !first {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{} ({} != {})", name, a, b)?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[track_caller]
+fn failed(
+    left: fmt::Arguments,
+    right: fmt::Arguments,
+    diff: &[(&'static str, usize, usize)],
+    msg: Option<fmt::Arguments>,
+) -> ! {
+    match msg {
+        Some(msg) => panic!(
+            "assertion `left == right` failed: {}\n   left: {}\n  right: {}\ndiffers: {}",
+            msg,
+            left,
+            right,
+            Diff(diff)
+        ),
+        None => panic!(
+            "assertion `left == right` failed\n   left: {}\n  right: {}\ndiffers: {}",
+            left,
+            right,
+            Diff(diff)
+        ),
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_pos_eq(left: Pos, right: Pos, msg: Option<fmt::Arguments>) {
+    if left != right {
+        failed(
+            format_args!("{}:{}", left.line, left.column),
+            format_args!("{}:{}", right.line, right.column),
+            &[
+                ("line", left.line, right.line),
+                ("column", left.column, right.column),
+            ],
+            msg,
+        )
+    }
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn __assert_loc_eq(left: Loc, right: Loc, msg: Option<fmt::Arguments>) {
+    if left != right {
+        let (l, r) = (left, right);
+        failed(
+            format_args!(
+                "{}:{}-{}:{}",
+                l.from.line, l.from.column, l.to.line, l.to.column
+            ),
+            format_args!(
+                "{}:{}-{}:{}",
+                r.from.line, r.from.column, r.to.line, r.to.column
+            ),
+            &[
+                ("from.line", l.from.line, r.from.line),
+                ("from.column", l.from.column, r.from.column),
+                ("to.line", l.to.line, r.to.line),
+                ("to.column", l.to.column, r.to.column),
+            ],
+            msg,
+        )
+    }
+}
+
+/// Asserts two Pos are equal, printing them as `line:column` and naming the differing coordinates
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// assert_pos_eq!(pos(1, 2), pos(1, 2));
+/// assert_pos_eq!(pos(1, 2), [1, 2], "token {}", 3);
+/// ```
+#[macro_export]
+macro_rules! assert_pos_eq {
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__assert_pos_eq($left, $crate::Pos::from($right), None)
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::__assert_pos_eq($left, $crate::Pos::from($right), Some(format_args!($($arg)+)))
+    };
+}
+
+/// Asserts two Loc are equal, printing them as `line:column-line:column` and naming the differing coordinates  
+/// The expected loc can also be given as four literal coordinates
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let l = locof!(1, 2, 3, 4);
+/// assert_loc_eq!(l, locof!(1, 2, 3, 4));
+/// assert_loc_eq!(l, 1, 2, 3, 4);
+/// assert_loc_eq!(l, l, "token {}", 3);
+/// ```
+#[macro_export]
+macro_rules! assert_loc_eq {
+    ($left:expr, $a:literal, $b:literal, $c:literal, $d:literal $(,)?) => {
+        $crate::__assert_loc_eq($left, $crate::Loc::new_at($a, $b, $c, $d), None)
+    };
+    ($left:expr, $right:expr $(,)?) => {
+        $crate::__assert_loc_eq($left, $crate::Loc::from($right), None)
+    };
+    ($left:expr, $right:expr, $($arg:tt)+) => {
+        $crate::__assert_loc_eq($left, $crate::Loc::from($right), Some(format_args!($($arg)+)))
+    };
+}
@@ -0,0 +1,103 @@
+use crate::{Loc, Pos};
+
+/// Zero based `Pos` or `Loc`, as in LSP and [`LineIndex`](crate::LineIndex)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Default)]
+#[repr(transparent)]
+pub struct ZeroBased<T>(pub T);
+
+/// One based `Pos` or `Loc`, as in [`line!`] and most compiler messages
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Default)]
+#[repr(transparent)]
+pub struct OneBased<T>(pub T);
+
+/// `Pos` whose column counts bytes of the line instead of chars  
+/// **The column of a `Pos` is in chars everywhere else in the crate**,
+/// convert with [`ByteColumn::to_char_col`] before using it as a `Pos`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Default)]
+#[repr(transparent)]
+pub struct ByteColumn<T>(pub T);
+
+impl ZeroBased<Pos> {
+    /// Shift to one based
+    #[inline]
+    pub const fn to_one_based(self) -> OneBased<Pos> {
+        OneBased(Pos::new(
+            self.0.line.saturating_add(1),
+            self.0.column.saturating_add(1),
+        ))
+    }
+}
+impl ZeroBased<Loc> {
+    /// Shift to one based
+    #[inline]
+    pub const fn to_one_based(self) -> OneBased<Loc> {
+        OneBased(Loc::new(
+            ZeroBased(self.0.from).to_one_based().0,
+            ZeroBased(self.0.to).to_one_based().0,
+        ))
+    }
+}
+impl OneBased<Pos> {
+    /// Shift to zero based, `None` if the line or column is 0
+    #[inline]
+    pub const fn to_zero_based(self) -> Option<ZeroBased<Pos>> {
+        if self.0.line == 0 || self.0.column == 0 {
+            None
+        } else {
+            Some(ZeroBased(Pos::new(self.0.line - 1, self.0.column - 1)))
+        }
+    }
+}
+impl OneBased<Loc> {
+    /// Shift to zero based, `None` if any line or column is 0
+    #[inline]
+    pub const fn to_zero_based(self) -> Option<ZeroBased<Loc>> {
+        match (
+            OneBased(self.0.from).to_zero_based(),
+            OneBased(self.0.to).to_zero_based(),
+        ) {
+            (Some(from), Some(to)) => Some(ZeroBased(Loc::new(from.0, to.0))),
+            _ => None,
+        }
+    }
+}
+
+impl From<ZeroBased<Pos>> for OneBased<Pos> {
+    #[inline]
+    fn from(p: ZeroBased<Pos>) -> Self {
+        p.to_one_based()
+    }
+}
+impl From<ZeroBased<Loc>> for OneBased<Loc> {
+    #[inline]
+    fn from(l: ZeroBased<Loc>) -> Self {
+        l.to_one_based()
+    }
+}
+
+impl Pos {
+    /// Pos with a byte column, as produced by byte oriented parsers
+    #[inline]
+    pub const fn from_byte_col(line: usize, byte_col: usize) -> ByteColumn<Pos> {
+        ByteColumn(Pos::new(line, byte_col))
+    }
+    /// Byte column of this char column in the line text  
+    /// `None` if the column is past the line end
+    pub fn to_byte_col(&self, line_text: &str) -> Option<ByteColumn<Pos>> {
+        let byte_col = match line_text.char_indices().nth(self.column) {
+            Some((i, _)) => i,
+            None if line_text.chars().count() == self.column => line_text.len(),
+            None => return None,
+        };
+        Some(Pos::from_byte_col(self.line, byte_col))
+    }
+}
+
+impl ByteColumn<Pos> {
+    /// Char column of this byte column in the line text  
+    /// `None` if the byte column is past the line end or not on a char boundary
+    pub fn to_char_col(&self, line_text: &str) -> Option<Pos> {
+        let before = line_text.get(..self.0.column)?;
+        Some(self.0.with_column(before.chars().count()))
+    }
+}
@@ -0,0 +1,41 @@
+use crate::Loc;
+use core::cmp::Ordering;
+
+/// Loc with reversed `Ord`, so a max heap like `BinaryHeap<ByStart>` pops the earliest loc first
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// use std::collections::BinaryHeap;
+///
+/// let mut queue: BinaryHeap<_> = vec![locof!(3, 0, 3, 1), locof!(1, 0, 1, 4)]
+///     .into_iter()
+///     .map(ByStart)
+///     .collect();
+/// queue.push(ByStart(locof!(2, 0, 2, 1)));
+/// assert_eq!(queue.pop(), Some(ByStart(locof!(1, 0, 1, 4))));
+/// assert_eq!(queue.pop().map(|b| b.0), Some(locof!(2, 0, 2, 1)));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(transparent)]
+pub struct ByStart(pub Loc);
+
+impl Ord for ByStart {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.0.cmp(&self.0)
+    }
+}
+
+impl PartialOrd for ByStart {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<Loc> for ByStart {
+    #[inline]
+    fn from(loc: Loc) -> Self {
+        Self(loc)
+    }
+}
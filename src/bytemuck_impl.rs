@@ -0,0 +1,33 @@
+//! bytemuck `Pod` and `Zeroable` for [`Pos`], [`Loc`], [`SmallPos`] and [`SmallLoc`]
+//!
+//! All are `#[repr(C)]` without padding, fields in declaration order.  
+//! Casts give native endian bytes, and `usize` fields follow the pointer width,
+//! so files are only readable on machines of the same endianness and width,
+//! [`SmallPos`] and [`SmallLoc`] at least have the same width everywhere.
+
+use crate::{Loc, Pos, SmallLoc, SmallPos};
+use bytemuck::{Pod, Zeroable};
+use core::mem::{align_of, size_of};
+
+// field order is checked by `test_bytemuck_layout`, sizes and alignments here
+const _: () = {
+    assert!(size_of::<Pos>() == 2 * size_of::<usize>());
+    assert!(align_of::<Pos>() == align_of::<usize>());
+    assert!(size_of::<Loc>() == 2 * size_of::<Pos>());
+    assert!(align_of::<Loc>() == align_of::<Pos>());
+    assert!(size_of::<SmallPos>() == 8);
+    assert!(align_of::<SmallPos>() == 4);
+    assert!(size_of::<SmallLoc>() == 16);
+    assert!(align_of::<SmallLoc>() == 4);
+};
+
+// SAFETY: repr(C) structs of integers without padding, as asserted above,
+// any bit pattern is valid and zero is `Pos::zero`, `Loc::zero` and so on
+unsafe impl Zeroable for Pos {}
+unsafe impl Pod for Pos {}
+unsafe impl Zeroable for Loc {}
+unsafe impl Pod for Loc {}
+unsafe impl Zeroable for SmallPos {}
+unsafe impl Pod for SmallPos {}
+unsafe impl Zeroable for SmallLoc {}
+unsafe impl Pod for SmallLoc {}
@@ -0,0 +1,43 @@
+//! codespan `Location` is zero based like `Pos`, with columns in chars,
+//! its one based `LineNumber`/`ColumnNumber` are only for display
+
+use crate::{LineIndex, Loc, Pos};
+use codespan::{ByteIndex, ColumnIndex, Location, Span};
+use core::convert::TryFrom;
+use core::num::TryFromIntError;
+
+impl From<Location> for Pos {
+    #[inline]
+    fn from(l: Location) -> Self {
+        Self::new(l.line.to_usize(), l.column.to_usize())
+    }
+}
+impl TryFrom<Pos> for Location {
+    type Error = TryFromIntError;
+
+    /// Fails if the line or column is out of `u32`
+    #[inline]
+    fn try_from(p: Pos) -> Result<Self, Self::Error> {
+        Ok(Location::new(
+            codespan::LineIndex(u32::try_from(p.line)?),
+            ColumnIndex(u32::try_from(p.column)?),
+        ))
+    }
+}
+
+impl Loc {
+    /// From a codespan byte span, `None` if not in the index
+    pub fn from_codespan_span(span: Span, index: &LineIndex) -> Option<Loc> {
+        let from = index.pos_of(span.start().to_usize())?;
+        let to = index.pos_of(span.end().to_usize())?;
+        Some(Loc::new(from, to))
+    }
+
+    /// To a codespan byte span, `None` if not in the index or out of `u32`
+    pub fn to_codespan_span(&self, index: &LineIndex) -> Option<Span> {
+        let range = self.byte_range(index)?;
+        let start = u32::try_from(range.start).ok()?;
+        let end = u32::try_from(range.end).ok()?;
+        Some(Span::new(ByteIndex(start), ByteIndex(end)))
+    }
+}
@@ -0,0 +1,454 @@
+//! The API [`Pos`](crate::Pos) and [`Loc`](crate::Loc) share with [`SmallPos`](crate::SmallPos) and [`SmallLoc`](crate::SmallLoc),
+//! generated for both widths so they cannot drift
+
+/// Inherent methods, `Default`, the ops and the compact `Debug` of a pos and loc pair with `$int` fields
+macro_rules! pos_loc_common {
+    ($pos:ident, $loc:ident, $int:ty) => {
+        impl $pos {
+            /// Placeholder for unknown positions
+            pub const DUMMY: Self = Self::new_same(<$int>::MAX);
+
+            #[doc = concat!("Is [`", stringify!($pos), "::DUMMY`]")]
+            #[inline]
+            pub const fn is_dummy(&self) -> bool {
+                self.line == <$int>::MAX && self.column == <$int>::MAX
+            }
+            /// New zero
+            #[inline]
+            pub const fn zero() -> Self {
+                Self::new_same(0)
+            }
+            /// New at
+            #[inline]
+            pub const fn new(line: $int, column: $int) -> Self {
+                Self { line, column }
+            }
+            /// New same value
+            #[inline]
+            pub const fn new_same(value: $int) -> Self {
+                Self::new(value, value)
+            }
+            /// The line, same as the field
+            #[inline]
+            pub const fn line(&self) -> $int {
+                self.line
+            }
+            /// The column, same as the field
+            #[inline]
+            pub const fn column(&self) -> $int {
+                self.column
+            }
+            /// Split into `(line, column)`
+            #[inline]
+            pub const fn into_parts(self) -> ($int, $int) {
+                (self.line, self.column)
+            }
+            /// Copy with another line
+            #[inline]
+            pub const fn with_line(self, line: $int) -> Self {
+                Self::new(line, self.column)
+            }
+            /// Copy with another column
+            #[inline]
+            pub const fn with_column(self, column: $int) -> Self {
+                Self::new(self.line, column)
+            }
+            /// Moves a pos relative to origin into absolute coordinates
+            #[inline]
+            pub const fn rebase(self, origin: $pos) -> Self {
+                if self.line == 0 {
+                    Self::new(origin.line, origin.column + self.column)
+                } else {
+                    Self::new(origin.line + self.line, self.column)
+                }
+            }
+            /// This pos relative to origin, saturating before the origin, the inverse of
+            #[doc = concat!("[`", stringify!($pos), "::rebase`]")]
+            #[inline]
+            pub const fn relative_to(self, origin: $pos) -> Self {
+                if self.line == origin.line {
+                    Self::new(0, self.column.saturating_sub(origin.column))
+                } else {
+                    Self::new(self.line.saturating_sub(origin.line), self.column)
+                }
+            }
+            /// `self < other` in const
+            #[inline]
+            const fn const_lt(&self, other: &$pos) -> bool {
+                self.line < other.line || (self.line == other.line && self.column < other.column)
+            }
+            /// Snap into the bounds, `bounds.from` if before, `bounds.to` if after
+            /// Reversed bounds are normalized first
+            #[inline]
+            pub const fn clamp_to(self, bounds: $loc) -> $pos {
+                let bounds = bounds.normalize();
+                if self.const_lt(&bounds.from) {
+                    bounds.from
+                } else if bounds.to.const_lt(&self) {
+                    bounds.to
+                } else {
+                    self
+                }
+            }
+            /// Map the line
+            #[inline]
+            pub fn map_line(self, f: impl FnOnce($int) -> $int) -> Self {
+                Self::new(f(self.line), self.column)
+            }
+            /// Map the column
+            #[inline]
+            pub fn map_column(self, f: impl FnOnce($int) -> $int) -> Self {
+                Self::new(self.line, f(self.column))
+            }
+            /// Equal within the tolerances
+            /// Symmetric and reflexive but not transitive
+            #[inline]
+            pub const fn approx_eq(&self, other: &$pos, line_tol: $int, col_tol: $int) -> bool {
+                self.line.abs_diff(other.line) <= line_tol
+                    && self.column.abs_diff(other.column) <= col_tol
+            }
+        }
+
+        impl $loc {
+            /// Placeholder for unknown locations
+            pub const DUMMY: Self = Self::new_same_pos($pos::DUMMY);
+
+            #[doc = concat!("Is [`", stringify!($loc), "::DUMMY`]")]
+            #[inline]
+            pub const fn is_dummy(&self) -> bool {
+                self.from.is_dummy() && self.to.is_dummy()
+            }
+            /// New at
+            #[inline]
+            pub const fn new(from: $pos, to: $pos) -> Self {
+                Self { from, to }
+            }
+            /// The `from` field
+            #[doc = concat!("Not named `from` so [`", stringify!($loc), "::from`](From::from) keeps resolving to the conversions")]
+            #[inline]
+            pub const fn start(&self) -> $pos {
+                self.from
+            }
+            /// The `to` field
+            #[inline]
+            pub const fn end(&self) -> $pos {
+                self.to
+            }
+            /// The line of `from`
+            #[inline]
+            pub const fn start_line(&self) -> $int {
+                self.from.line
+            }
+            /// All four fields, `(from.line, from.column, to.line, to.column)`
+            #[inline]
+            pub const fn to_tuple(&self) -> ($int, $int, $int, $int) {
+                (
+                    self.from.line,
+                    self.from.column,
+                    self.to.line,
+                    self.to.column,
+                )
+            }
+            /// New at
+            #[inline]
+            pub const fn new_at(
+                from_line: $int,
+                from_column: $int,
+                to_line: $int,
+                to_column: $int,
+            ) -> Self {
+                Self::new(
+                    $pos::new(from_line, from_column),
+                    $pos::new(to_line, to_column),
+                )
+            }
+            /// New zero
+            #[inline]
+            pub const fn zero() -> Self {
+                Self::new_same_pos($pos::zero())
+            }
+            /// New same value
+            #[inline]
+            pub const fn new_same(value: $int) -> Self {
+                Self::new_same_pos($pos::new_same(value))
+            }
+            /// New same value
+            #[inline]
+            pub const fn new_same_pos(pos: $pos) -> Self {
+                Self::new(pos, pos)
+            }
+            /// Map both ends
+            #[inline]
+            pub fn map(self, f: impl Fn($pos) -> $pos) -> Self {
+                Self::new(f(self.from), f(self.to))
+            }
+            /// Middle of a single line loc, the column is `(from.column + to.column) / 2` rounded down
+            /// `None` if the loc spans lines
+            #[inline]
+            pub const fn center(&self) -> Option<$pos> {
+                if self.from.line == self.to.line {
+                    let (a, b) = (self.from.column, self.to.column);
+                    Some(self.from.with_column(a / 2 + b / 2 + (a % 2 + b % 2) / 2))
+                } else {
+                    None
+                }
+            }
+            /// Same as [`Ord::cmp`], a total order by `from` then `to`,
+            /// stable across versions so locs can key a `BTreeMap`
+            #[inline]
+            pub fn total_cmp(&self, other: &$loc) -> core::cmp::Ordering {
+                self.cmp(other)
+            }
+            /// Both ends equal within the tolerances, see
+            #[doc = concat!("[`", stringify!($pos), "::approx_eq`]")]
+            #[inline]
+            pub const fn approx_eq(&self, other: &$loc, line_tol: $int, col_tol: $int) -> bool {
+                self.from.approx_eq(&other.from, line_tol, col_tol)
+                    && self.to.approx_eq(&other.to, line_tol, col_tol)
+            }
+            /// Swap the ends if reversed, so `from <= to`
+            #[inline]
+            pub const fn normalize(self) -> $loc {
+                if self.to.const_lt(&self.from) {
+                    Self::new(self.to, self.from)
+                } else {
+                    self
+                }
+            }
+            /// One ends where the other starts, in either order
+            #[inline]
+            pub fn is_adjacent_to(&self, other: &$loc) -> bool {
+                self.to == other.from || other.to == self.from
+            }
+            #[doc = concat!("Equal after [`", stringify!($loc), "::normalize`], the order of the ends does not matter")]
+            #[inline]
+            pub fn eq_unordered(&self, other: &$loc) -> bool {
+                self.normalize() == other.normalize()
+            }
+            /// Split into `(from, to)`
+            #[inline]
+            pub const fn into_parts(self) -> ($pos, $pos) {
+                (self.from, self.to)
+            }
+            /// Zero width loc at `from`
+            #[inline]
+            pub const fn start_loc(&self) -> $loc {
+                Self::new_same_pos(self.from)
+            }
+            /// Zero width loc at `to`
+            #[inline]
+            pub const fn end_loc(&self) -> $loc {
+                Self::new_same_pos(self.to)
+            }
+            /// Does the loc touch the line, `from.line <= line <= to.line`
+            #[inline]
+            pub const fn touches_line(&self, line: $int) -> bool {
+                self.from.line <= line && line <= self.to.line
+            }
+            #[doc = concat!("Lines the loc touches, `from.line..=to.line`, empty if either end is [`", stringify!($pos), "::DUMMY`]")]
+            #[inline]
+            pub fn lines(&self) -> core::ops::RangeInclusive<$int> {
+                if self.from.is_dummy() || self.to.is_dummy() {
+                    #[allow(clippy::reversed_empty_ranges)]
+                    return 1..=0;
+                }
+                self.from.line..=self.to.line
+            }
+            #[doc = concat!("Lines the loc touches as a half open range, `from.line..to.line + 1`, empty if either end is [`", stringify!($pos), "::DUMMY`]  ")]
+            /// Slices a `Vec` of source lines as `lines.get(loc.line_range())`
+            #[inline]
+            pub const fn line_range(&self) -> core::ops::Range<$int> {
+                if self.from.is_dummy() || self.to.is_dummy() {
+                    return 0..0;
+                }
+                self.from.line..self.to.line.saturating_add(1)
+            }
+            /// Does the loc cover the whole line including its line break,
+            /// starting at or before the line start and ending on a later line
+            #[inline]
+            pub const fn fully_covers_line(&self, line: $int) -> bool {
+                (self.from.line < line || (self.from.line == line && self.from.column == 0))
+                    && line < self.to.line
+            }
+            /// Is the pos in `from..to`
+            #[inline]
+            pub fn contains(&self, pos: $pos) -> bool {
+                self.from <= pos && pos < self.to
+            }
+            /// Is the other loc inside this one
+            #[inline]
+            pub fn contains_loc(&self, other: &$loc) -> bool {
+                self.from <= other.from && other.to <= self.to
+            }
+            /// Smallest loc covering both
+            #[inline]
+            pub fn merge(&self, other: &$loc) -> $loc {
+                Self::new(self.from.min(other.from), self.to.max(other.to))
+            }
+            /// Overlapping part, `None` if disjoint
+            #[inline]
+            pub fn intersection(&self, other: &$loc) -> Option<$loc> {
+                let from = self.from.max(other.from);
+                let to = self.to.min(other.to);
+                if from <= to {
+                    Some(Self::new(from, to))
+                } else {
+                    None
+                }
+            }
+            /// Moves a loc relative to origin into absolute coordinates,
+            /// the column is only shifted on the origin's line
+            #[inline]
+            pub fn rebase(&self, origin: $pos) -> $loc {
+                Self::new(self.from.rebase(origin), self.to.rebase(origin))
+            }
+            #[doc = concat!("This loc relative to `outer.from`, the inverse of [`", stringify!($loc), "::rebase`],")]
+            /// `None` if not inside outer
+            #[inline]
+            pub fn relative_within(&self, outer: &$loc) -> Option<$loc> {
+                if outer.contains_loc(self) {
+                    Some(Self::new(
+                        self.from.relative_to(outer.from),
+                        self.to.relative_to(outer.from),
+                    ))
+                } else {
+                    None
+                }
+            }
+        }
+
+        impl Default for $pos {
+            #[inline(always)]
+            fn default() -> Self {
+                Self::zero()
+            }
+        }
+
+        impl Default for $loc {
+            #[inline(always)]
+            fn default() -> Self {
+                Self::zero()
+            }
+        }
+
+        #[doc = concat!("`", stringify!($pos), "(1:2)`, `{:#?}` prints the fields")]
+        #[cfg(feature = "compact-debug")]
+        impl core::fmt::Debug for $pos {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                if f.alternate() {
+                    f.debug_struct(stringify!($pos))
+                        .field("line", &self.line)
+                        .field("column", &self.column)
+                        .finish()
+                } else {
+                    write!(
+                        f,
+                        concat!(stringify!($pos), "({})"),
+                        $crate::Pos::from(*self).bare()
+                    )
+                }
+            }
+        }
+
+        #[doc = concat!("`", stringify!($loc), "(1:2..3:4)`, `{:#?}` prints the fields")]
+        #[cfg(feature = "compact-debug")]
+        impl core::fmt::Debug for $loc {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                if f.alternate() {
+                    f.debug_struct(stringify!($loc))
+                        .field("from", &self.from)
+                        .field("to", &self.to)
+                        .finish()
+                } else {
+                    let $crate::Loc { from, to } = $crate::Loc::from(*self);
+                    write!(
+                        f,
+                        concat!(stringify!($loc), "({}..{})"),
+                        from.bare(),
+                        to.bare()
+                    )
+                }
+            }
+        }
+
+        #[doc = concat!("Same as [`", stringify!($loc), "::merge`]")]
+        impl core::ops::BitOr for $loc {
+            type Output = $loc;
+
+            #[inline]
+            fn bitor(self, other: $loc) -> $loc {
+                self.merge(&other)
+            }
+        }
+        #[doc = concat!("Same as [`", stringify!($loc), "::intersection`], but disjoint locs give the zero width loc at the end of the earlier one")]
+        impl core::ops::BitAnd for $loc {
+            type Output = $loc;
+
+            #[inline]
+            fn bitand(self, other: $loc) -> $loc {
+                self.intersection(&other)
+                    .unwrap_or_else(|| $loc::new_same_pos(self.to.min(other.to)))
+            }
+        }
+        #[doc = concat!("Moves the column forward, saturating at `", stringify!($int), "::MAX`")]
+        impl core::ops::Add<$int> for $pos {
+            type Output = $pos;
+
+            #[inline]
+            fn add(self, columns: $int) -> $pos {
+                self.with_column(self.column.saturating_add(columns))
+            }
+        }
+        /// Moves the column back, saturating at 0
+        impl core::ops::Sub<$int> for $pos {
+            type Output = $pos;
+
+            #[inline]
+            fn sub(self, columns: $int) -> $pos {
+                self.with_column(self.column.saturating_sub(columns))
+            }
+        }
+        impl core::ops::AddAssign<$int> for $pos {
+            #[inline]
+            fn add_assign(&mut self, columns: $int) {
+                *self = *self + columns;
+            }
+        }
+        impl core::ops::SubAssign<$int> for $pos {
+            #[inline]
+            fn sub_assign(&mut self, columns: $int) {
+                *self = *self - columns;
+            }
+        }
+        /// Moves both ends forward by columns, saturating
+        impl core::ops::Add<$int> for $loc {
+            type Output = $loc;
+
+            #[inline]
+            fn add(self, columns: $int) -> $loc {
+                $loc::new(self.from + columns, self.to + columns)
+            }
+        }
+        /// Moves both ends back by columns, saturating at 0
+        impl core::ops::Sub<$int> for $loc {
+            type Output = $loc;
+
+            #[inline]
+            fn sub(self, columns: $int) -> $loc {
+                $loc::new(self.from - columns, self.to - columns)
+            }
+        }
+        impl core::ops::AddAssign<$int> for $loc {
+            #[inline]
+            fn add_assign(&mut self, columns: $int) {
+                *self = *self + columns;
+            }
+        }
+        impl core::ops::SubAssign<$int> for $loc {
+            #[inline]
+            fn sub_assign(&mut self, columns: $int) {
+                *self = *self - columns;
+            }
+        }
+    };
+}
@@ -0,0 +1,189 @@
+//! [`CompactLoc`], a [`Loc`] packed into one `u64` for dense side tables
+//!
+//! Bit layout from the most significant bit:
+//!
+//! | bits    | width | field                    | range                |
+//! |---------|-------|--------------------------|----------------------|
+//! | 63..=44 | 20    | `from.line`              | `0..=0xffffe`        |
+//! | 43..=32 | 12    | `from.column`            | `0..=0xfff`          |
+//! | 31..=12 | 20    | `to.line - from.line`    | `0..=0xfffff`        |
+//! | 11..=0  | 12    | `to.column`              | `0..=0xfff`          |
+//!
+//! `from.line` of `0xfffff` is the overflow marker,
+//! the low 44 bits then index the overflow side table of a [`CompactLocTable`].
+
+use crate::{Loc, Pos};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::iter::FromIterator;
+
+const LINE_BITS: u32 = 20;
+const COLUMN_BITS: u32 = 12;
+const LINE_MASK: u64 = (1 << LINE_BITS) - 1;
+const COLUMN_MASK: u64 = (1 << COLUMN_BITS) - 1;
+const OVERFLOW: u64 = LINE_MASK;
+#[cfg(feature = "alloc")]
+const INDEX_MASK: u64 = (1 << (64 - LINE_BITS)) - 1;
+
+/// A [`Loc`] packed into one `u64`, see the [module docs](self) for the layout
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let packed = CompactLoc::try_pack(locof!(12, 4, 14, 9)).unwrap();
+/// assert_eq!(packed.unpack(), locof!(12, 4, 14, 9));
+/// assert_eq!(CompactLoc::try_pack(locof!(0, 4096, 0, 4097)), None);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+#[repr(transparent)]
+pub struct CompactLoc(u64);
+
+impl CompactLoc {
+    /// Largest packable `from.line`, `0xfffff` is the overflow marker
+    pub const MAX_LINE: usize = OVERFLOW as usize - 1;
+    /// Largest packable column
+    pub const MAX_COLUMN: usize = COLUMN_MASK as usize;
+    /// Largest packable `to.line - from.line`
+    pub const MAX_LINE_DELTA: usize = LINE_MASK as usize;
+
+    /// Pack the loc, `None` if a field is out of its range or `to.line < from.line`
+    pub const fn try_pack(loc: Loc) -> Option<CompactLoc> {
+        let Loc { from, to } = loc;
+        if from.line > Self::MAX_LINE
+            || from.column > Self::MAX_COLUMN
+            || to.column > Self::MAX_COLUMN
+            || to.line < from.line
+            || to.line - from.line > Self::MAX_LINE_DELTA
+        {
+            return None;
+        }
+        Some(CompactLoc(
+            (from.line as u64) << 44
+                | (from.column as u64) << 32
+                | ((to.line - from.line) as u64) << 12
+                | to.column as u64,
+        ))
+    }
+
+    /// Unpack the loc, [`Loc::DUMMY`] if it is an overflow marker
+    pub const fn unpack(self) -> Loc {
+        if self.is_overflow() {
+            return Loc::DUMMY;
+        }
+        let line = (self.0 >> 44) as usize;
+        let column = (self.0 >> 32 & COLUMN_MASK) as usize;
+        let delta = (self.0 >> 12 & LINE_MASK) as usize;
+        let to_column = (self.0 & COLUMN_MASK) as usize;
+        Loc::new(Pos::new(line, column), Pos::new(line + delta, to_column))
+    }
+
+    /// Is an overflow marker, the loc lives in a side table
+    #[inline]
+    pub const fn is_overflow(self) -> bool {
+        self.0 >> 44 == OVERFLOW
+    }
+
+    /// The raw bits
+    #[inline]
+    pub const fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// From raw bits of [`CompactLoc::to_bits`]
+    #[inline]
+    pub const fn from_bits(bits: u64) -> Self {
+        CompactLoc(bits)
+    }
+
+    /// Overflow marker pointing at the side table entry
+    #[cfg(feature = "alloc")]
+    const fn overflow(index: usize) -> Self {
+        CompactLoc(OVERFLOW << 44 | (index as u64 & INDEX_MASK))
+    }
+
+    /// Side table index of an overflow marker
+    #[cfg(feature = "alloc")]
+    const fn overflow_index(self) -> usize {
+        (self.0 & INDEX_MASK) as usize
+    }
+}
+
+/// Locs packed as [`CompactLoc`], locs that do not fit go to a side `Vec` transparently
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let mut table = CompactLocTable::new();
+/// table.push(locof!(1, 2, 1, 8));
+/// table.push(locof!(1, 2, 1, 5000));
+/// assert_eq!(table.get(1), Some(locof!(1, 2, 1, 5000)));
+/// assert_eq!(table.overflow_len(), 1);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct CompactLocTable {
+    packed: Vec<CompactLoc>,
+    overflow: Vec<Loc>,
+}
+
+#[cfg(feature = "alloc")]
+impl CompactLocTable {
+    /// New empty
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a loc, returns its index
+    pub fn push(&mut self, loc: Loc) -> usize {
+        let packed = CompactLoc::try_pack(loc).unwrap_or_else(|| {
+            self.overflow.push(loc);
+            CompactLoc::overflow(self.overflow.len() - 1)
+        });
+        self.packed.push(packed);
+        self.packed.len() - 1
+    }
+
+    /// The loc at the index, `None` if out of the table
+    pub fn get(&self, index: usize) -> Option<Loc> {
+        let packed = *self.packed.get(index)?;
+        Some(if packed.is_overflow() {
+            self.overflow[packed.overflow_index()]
+        } else {
+            packed.unpack()
+        })
+    }
+
+    /// Count of locs
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.packed.len()
+    }
+
+    /// Is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.packed.is_empty()
+    }
+
+    /// Count of locs in the side table
+    #[inline]
+    pub fn overflow_len(&self) -> usize {
+        self.overflow.len()
+    }
+
+    /// The locs in order
+    pub fn iter(&self) -> impl Iterator<Item = Loc> + '_ {
+        (0..self.len()).filter_map(move |i| self.get(i))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromIterator<Loc> for CompactLocTable {
+    fn from_iter<I: IntoIterator<Item = Loc>>(iter: I) -> Self {
+        let mut table = Self::new();
+        for loc in iter {
+            table.push(loc);
+        }
+        table
+    }
+}
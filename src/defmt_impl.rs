@@ -0,0 +1,20 @@
+use crate::{Loc, Pos};
+use defmt::{write, Format, Formatter};
+
+impl Format for Pos {
+    /// `line:column`
+    fn format(&self, f: Formatter) {
+        write!(f, "{=usize}:{=usize}", self.line, self.column)
+    }
+}
+
+impl Format for Loc {
+    /// `line:column..line:column`
+    fn format(&self, f: Formatter) {
+        write!(
+            f,
+            "{=usize}:{=usize}..{=usize}:{=usize}",
+            self.from.line, self.from.column, self.to.line, self.to.column
+        )
+    }
+}
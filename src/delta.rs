@@ -0,0 +1,176 @@
+//! Delta encoded `Loc` streams for compact token tables
+//!
+//! Each loc is four zigzag LEB128 varints:
+//! `from.line` relative to the previous `from.line`,
+//! `from.column` relative to the previous `from.column` on the same line else to `0`,
+//! `to.line` relative to `from.line`,
+//! `to.column` relative to `from.column` on the same line else to `0`.  
+//! Any sequence round trips, sorted ones encode smallest.
+//!
+//! # Examples
+//! ```
+//! # use srcpos::*;
+//! let locs = [locof!(0, 0, 0, 2), locof!(0, 3, 0, 7), locof!(1, 4, 1, 7)];
+//! let bytes = delta::encode_all(&locs);
+//! assert_eq!(bytes.len(), 12);
+//! let back: Result<Vec<Loc>, _> = delta::decode(&bytes).collect();
+//! assert_eq!(back.unwrap(), locs);
+//! ```
+
+use crate::{Loc, Pos};
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Error of [`LocDeltaDecoder`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DeltaDecodeError {
+    /// The input ends inside a loc
+    Truncated,
+    /// A varint is longer than 64 bits
+    Overflow,
+}
+
+impl fmt::Display for DeltaDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Truncated => "delta stream ends inside a loc",
+            Self::Overflow => "delta stream varint overflows 64 bits",
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeltaDecodeError {}
+
+/// Encoder of a delta stream, see the [module docs](self)
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct LocDeltaEncoder {
+    prev: Pos,
+    bytes: Vec<u8>,
+}
+
+impl LocDeltaEncoder {
+    /// New empty
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a loc
+    pub fn push(&mut self, loc: Loc) {
+        let Loc { from, to } = loc;
+        let from_column_base = if from.line == self.prev.line {
+            self.prev.column
+        } else {
+            0
+        };
+        let to_column_base = if to.line == from.line { from.column } else { 0 };
+        self.write(from.line, self.prev.line);
+        self.write(from.column, from_column_base);
+        self.write(to.line, from.line);
+        self.write(to.column, to_column_base);
+        self.prev = from;
+    }
+
+    /// The encoded bytes
+    #[inline]
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    fn write(&mut self, value: usize, base: usize) {
+        let delta = value.wrapping_sub(base) as u64 as i64;
+        let mut v = ((delta << 1) ^ (delta >> 63)) as u64;
+        while v >= 0x80 {
+            self.bytes.push(v as u8 | 0x80);
+            v >>= 7;
+        }
+        self.bytes.push(v as u8);
+    }
+}
+
+/// Decoder of a delta stream, an iterator of locs, see the [module docs](self)  
+/// Stops after the first error
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LocDeltaDecoder<'a> {
+    prev: Pos,
+    bytes: &'a [u8],
+}
+
+impl<'a> LocDeltaDecoder<'a> {
+    /// New over the bytes
+    #[inline]
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            prev: Pos::zero(),
+            bytes,
+        }
+    }
+
+    fn read(&mut self, base: usize) -> Result<usize, DeltaDecodeError> {
+        let mut v = 0u64;
+        let mut shift = 0;
+        loop {
+            let (&b, rest) = self
+                .bytes
+                .split_first()
+                .ok_or(DeltaDecodeError::Truncated)?;
+            self.bytes = rest;
+            if shift == 63 && b > 1 || shift > 63 {
+                return Err(DeltaDecodeError::Overflow);
+            }
+            v |= ((b & 0x7f) as u64) << shift;
+            if b & 0x80 == 0 {
+                break;
+            }
+            shift += 7;
+        }
+        let delta = ((v >> 1) as i64) ^ -((v & 1) as i64);
+        Ok(base.wrapping_add(delta as usize))
+    }
+
+    fn read_loc(&mut self) -> Result<Loc, DeltaDecodeError> {
+        let line = self.read(self.prev.line)?;
+        let column_base = if line == self.prev.line {
+            self.prev.column
+        } else {
+            0
+        };
+        let from = Pos::new(line, self.read(column_base)?);
+        let line = self.read(from.line)?;
+        let column_base = if line == from.line { from.column } else { 0 };
+        let to = Pos::new(line, self.read(column_base)?);
+        self.prev = from;
+        Ok(Loc::new(from, to))
+    }
+}
+
+impl Iterator for LocDeltaDecoder<'_> {
+    type Item = Result<Loc, DeltaDecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let loc = self.read_loc();
+        if loc.is_err() {
+            self.bytes = &[];
+        }
+        Some(loc)
+    }
+}
+
+/// Encode all the locs
+pub fn encode_all(locs: &[Loc]) -> Vec<u8> {
+    let mut encoder = LocDeltaEncoder::new();
+    for &loc in locs {
+        encoder.push(loc);
+    }
+    encoder.finish()
+}
+
+/// Decode the locs
+#[inline]
+pub fn decode(bytes: &[u8]) -> LocDeltaDecoder<'_> {
+    LocDeltaDecoder::new(bytes)
+}
@@ -0,0 +1,152 @@
+//! Plain JSON diagnostic, a stable documented shape for tools without SARIF or LSP
+//!
+//! ```json
+//! {"version":1,"severity":"error","message":"...","code":"E001","file":"src/main.rs",
+//!  "loc":{"from":[1,2],"to":[3,4]},"labels":[{"file":"src/main.rs","loc":{"from":[0,0],"to":[0,1]},"message":"..."}]}
+//! ```
+//! `code` is left out when `None`, positions are the stored values of the [`Loc`].
+
+use crate::{Loc, Pos};
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::de::{self, Deserializer};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Severity of a [`Diagnostic`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// `"error"`
+    Error,
+    /// `"warning"`
+    Warning,
+    /// `"note"`
+    Note,
+    /// `"help"`
+    Help,
+}
+
+/// Secondary location of a [`Diagnostic`]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct DiagnosticLabel {
+    /// file
+    pub file: String,
+    /// location in the file
+    #[serde(with = "loc_arrays")]
+    pub loc: Loc,
+    /// message
+    pub message: String,
+}
+
+/// Diagnostic in the plain JSON format, see the [module docs](self)
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// format version, always [`Diagnostic::VERSION`]
+    #[serde(deserialize_with = "version")]
+    pub version: u32,
+    /// severity
+    pub severity: Severity,
+    /// message
+    pub message: String,
+    /// optional code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// file of the primary location
+    pub file: String,
+    /// primary location
+    #[serde(with = "loc_arrays")]
+    pub loc: Loc,
+    /// secondary locations
+    #[serde(default)]
+    pub labels: Vec<DiagnosticLabel>,
+}
+
+impl Diagnostic {
+    /// Version of the format
+    pub const VERSION: u32 = 1;
+
+    /// New without code and labels
+    pub fn new(
+        severity: Severity,
+        message: impl Into<String>,
+        file: impl Into<String>,
+        loc: Loc,
+    ) -> Self {
+        Self {
+            version: Self::VERSION,
+            severity,
+            message: message.into(),
+            code: None,
+            file: file.into(),
+            loc,
+            labels: Vec::new(),
+        }
+    }
+
+    /// With the code
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// With a label
+    pub fn with_label(
+        mut self,
+        file: impl Into<String>,
+        loc: Loc,
+        message: impl Into<String>,
+    ) -> Self {
+        self.labels.push(DiagnosticLabel {
+            file: file.into(),
+            loc,
+            message: message.into(),
+        });
+        self
+    }
+}
+
+#[cfg(feature = "json")]
+impl Diagnostic {
+    /// Single line JSON, for NDJSON output
+    pub fn to_json_line(&self) -> String {
+        serde_json::to_string(self).expect("diagnostic serializes to JSON")
+    }
+}
+
+/// Only [`Diagnostic::VERSION`] is accepted
+fn version<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let v = u32::deserialize(deserializer)?;
+    if v == Diagnostic::VERSION {
+        Ok(v)
+    } else {
+        Err(de::Error::invalid_value(
+            de::Unexpected::Unsigned(v as u64),
+            &"diagnostic format version 1",
+        ))
+    }
+}
+
+/// `Loc` as `{"from":[line,column],"to":[line,column]}`
+mod loc_arrays {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Arrays {
+        #[serde(with = "crate::serde_compact")]
+        from: Pos,
+        #[serde(with = "crate::serde_compact")]
+        to: Pos,
+    }
+
+    pub fn serialize<S: Serializer>(loc: &Loc, serializer: S) -> Result<S::Ok, S::Error> {
+        Arrays {
+            from: loc.from,
+            to: loc.to,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Loc, D::Error> {
+        Arrays::deserialize(deserializer).map(|a| Loc::new(a.from, a.to))
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{Loc, Pos};
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use core::fmt;
+
+/// Error of [`DiffRemapper::parse`], lines are one based lines of the diff
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum DiffParseError {
+    /// A `@@` line that is not `@@ -a[,b] +c[,d] @@`
+    BadHunkHeader { line: usize },
+    /// A hunk before any `---` file header
+    HunkOutsideFile { line: usize },
+    /// A hunk with fewer lines than its header counts, at the first stray line or the header if the diff ends
+    ShortHunk { line: usize },
+}
+
+impl fmt::Display for DiffParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadHunkHeader { line } => write!(f, "bad hunk header on diff line {}", line),
+            Self::HunkOutsideFile { line } => {
+                write!(f, "hunk without file header on diff line {}", line)
+            }
+            Self::ShortHunk { line } => write!(f, "hunk ends early on diff line {}", line),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DiffParseError {}
+
+/// Where an old line went, lines are zero based
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum LineMapping {
+    /// Unchanged at the same line
+    Same(usize),
+    /// Unchanged but shifted to this line
+    Moved(usize),
+    /// Deleted or replaced
+    Removed,
+}
+
+impl LineMapping {
+    /// The new line, `None` if removed
+    #[inline]
+    pub const fn line(self) -> Option<usize> {
+        match self {
+            Self::Same(line) | Self::Moved(line) => Some(line),
+            Self::Removed => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Hunk {
+    old_start: usize,
+    old_len: usize,
+    new_start: usize,
+    new_len: usize,
+    /// `b' '`, `b'-'` or `b'+'` per body line
+    ops: Vec<u8>,
+}
+
+/// Old to new line remapping of a unified diff, such as `git diff` output  
+/// Files are keyed by their old path without the `a/` prefix,
+/// or the new path for added files
+///
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let diff = "\
+/// --- a/lib.rs
+/// +++ b/lib.rs
+/// @@ -1,3 +1,3 @@
+/// +// header
+///  fn a() {}
+/// -fn b() {}
+///  fn c() {}
+/// ";
+/// let remap = DiffRemapper::parse(diff).unwrap();
+/// let file = remap.file("lib.rs");
+/// assert_eq!(file.remap_line(0), LineMapping::Moved(1));
+/// assert_eq!(file.remap_line(1), LineMapping::Removed);
+/// assert_eq!(file.remap_line(2), LineMapping::Same(2));
+/// assert_eq!(file.remap_loc(locof!(2, 3, 2, 4)), Some(locof!(2, 3, 2, 4)));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct DiffRemapper {
+    files: BTreeMap<String, Vec<Hunk>>,
+}
+
+impl DiffRemapper {
+    /// Parse the hunks of a unified diff, headers other than `---` and `+++` are skipped
+    pub fn parse(diff_text: &str) -> Result<Self, DiffParseError> {
+        let mut files = BTreeMap::new();
+        let mut old_path: Option<&str> = None;
+        let mut current: Option<(String, Vec<Hunk>)> = None;
+        let mut lines = diff_text.lines().enumerate().peekable();
+        while let Some((i, line)) = lines.next() {
+            if let Some(path) = line.strip_prefix("--- ") {
+                old_path = Some(diff_path(path));
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                if let Some((path, hunks)) = current.take() {
+                    files.insert(path, hunks);
+                }
+                let path = match old_path.take() {
+                    Some(old) if old != "/dev/null" => old,
+                    _ => diff_path(path),
+                };
+                current = Some((path.into(), Vec::new()));
+            } else if line.starts_with("@@") {
+                let (_, hunks) = current
+                    .as_mut()
+                    .ok_or(DiffParseError::HunkOutsideFile { line: i + 1 })?;
+                let mut hunk =
+                    hunk_header(line).ok_or(DiffParseError::BadHunkHeader { line: i + 1 })?;
+                let (mut old, mut new) = (hunk.old_len, hunk.new_len);
+                while old > 0 || new > 0 {
+                    let (j, body) = lines
+                        .next()
+                        .ok_or(DiffParseError::ShortHunk { line: i + 1 })?;
+                    let op = match body.as_bytes().first() {
+                        None => b' ',
+                        Some(b'\\') => continue,
+                        Some(&op) => op,
+                    };
+                    let short = DiffParseError::ShortHunk { line: j + 1 };
+                    match op {
+                        b' ' if old > 0 && new > 0 => (old, new) = (old - 1, new - 1),
+                        b'-' if old > 0 => old -= 1,
+                        b'+' if new > 0 => new -= 1,
+                        _ => return Err(short),
+                    }
+                    hunk.ops.push(op);
+                }
+                while lines.peek().is_some_and(|(_, l)| l.starts_with('\\')) {
+                    lines.next();
+                }
+                hunks.push(hunk);
+            }
+        }
+        if let Some((path, hunks)) = current {
+            files.insert(path, hunks);
+        }
+        Ok(Self { files })
+    }
+
+    /// Paths of the files in the diff
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Remapping of one file, files not in the diff map every line to itself
+    pub fn file(&self, path: &str) -> FileRemapper<'_> {
+        FileRemapper {
+            hunks: self.files.get(path).map_or(&[], Vec::as_slice),
+        }
+    }
+}
+
+/// Remapping of one file of a [`DiffRemapper`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct FileRemapper<'a> {
+    hunks: &'a [Hunk],
+}
+
+impl FileRemapper<'_> {
+    /// Where the zero based old line is in the new revision
+    pub fn remap_line(&self, old_line: usize) -> LineMapping {
+        let mut delta = 0isize;
+        for hunk in self.hunks {
+            if old_line < hunk.old_start {
+                break;
+            }
+            if old_line < hunk.old_start + hunk.old_len {
+                let (mut old, mut new) = (hunk.old_start, hunk.new_start);
+                for &op in &hunk.ops {
+                    match op {
+                        b'+' => new += 1,
+                        _ if old == old_line => {
+                            return if op == b'-' {
+                                LineMapping::Removed
+                            } else {
+                                mapping(old_line, new)
+                            };
+                        }
+                        _ if op == b'-' => old += 1,
+                        _ => (old, new) = (old + 1, new + 1),
+                    }
+                }
+            }
+            delta =
+                (hunk.new_start + hunk.new_len) as isize - (hunk.old_start + hunk.old_len) as isize;
+        }
+        mapping(old_line, old_line.wrapping_add_signed(delta))
+    }
+
+    /// The loc in the new revision, columns are kept  
+    /// `None` if a line of either end was removed, dummies stay dummies
+    pub fn remap_loc(&self, loc: Loc) -> Option<Loc> {
+        if loc.is_dummy() {
+            return Some(loc);
+        }
+        let from = self.remap_line(loc.from.line).line()?;
+        let to = self.remap_line(loc.to.line).line()?;
+        Some(Loc::new(
+            Pos::new(from, loc.from.column),
+            Pos::new(to, loc.to.column),
+        ))
+    }
+}
+
+fn mapping(old: usize, new: usize) -> LineMapping {
+    if old == new {
+        LineMapping::Same(new)
+    } else {
+        LineMapping::Moved(new)
+    }
+}
+
+/// Path of a `---` or `+++` header without the `a/` or `b/` prefix and trailing timestamp
+fn diff_path(header: &str) -> &str {
+    let path = header.split('\t').next().unwrap_or(header).trim_end();
+    path.strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path)
+}
+
+/// `@@ -a[,b] +c[,d] @@`, starts made zero based, an empty side starts after its line
+fn hunk_header(line: &str) -> Option<Hunk> {
+    let mut parts = line.strip_prefix("@@ ")?.split(' ');
+    let (old_start, old_len) = range(parts.next()?.strip_prefix('-')?)?;
+    let (new_start, new_len) = range(parts.next()?.strip_prefix('+')?)?;
+    if parts.next()? != "@@" {
+        return None;
+    }
+    Some(Hunk {
+        old_start,
+        old_len,
+        new_start,
+        new_len,
+        ops: Vec::new(),
+    })
+}
+
+fn range(s: &str) -> Option<(usize, usize)> {
+    let (start, len): (usize, usize) = match s.split_once(',') {
+        Some((start, len)) => (start.parse().ok()?, len.parse().ok()?),
+        None => (s.parse().ok()?, 1),
+    };
+    if len == 0 {
+        Some((start, 0))
+    } else {
+        Some((start.checked_sub(1)?, len))
+    }
+}
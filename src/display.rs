@@ -0,0 +1,301 @@
+use crate::{Loc, Pos, ZeroBased};
+use core::fmt;
+use core::fmt::Display;
+
+/// How every formatter renders [`Pos::DUMMY`] and [`Loc::DUMMY`], after the file name if it has one
+pub(crate) const UNKNOWN: &str = "<unknown>";
+
+/// Counts the chars written, to measure args for [`pad`]
+struct CharCount(usize);
+
+impl fmt::Write for CharCount {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+/// Passes on the first `left` chars written, for the precision of [`pad`]
+struct Truncate<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    left: usize,
+}
+
+impl fmt::Write for Truncate<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = s.char_indices().nth(self.left).map_or(s.len(), |(i, _)| i);
+        self.left -= s[..end].chars().count();
+        self.f.write_str(&s[..end])
+    }
+}
+
+/// Write args honoring the width, fill, align and precision of the formatter like [`fmt::Formatter::pad`],
+/// the args are formatted twice, once to count the chars and once to write them
+pub(crate) fn pad(f: &mut fmt::Formatter<'_>, args: fmt::Arguments<'_>) -> fmt::Result {
+    if f.width().is_none() && f.precision().is_none() {
+        return f.write_fmt(args);
+    }
+    let mut count = CharCount(0);
+    fmt::Write::write_fmt(&mut count, args)?;
+    let len = f.precision().map_or(count.0, |p| p.min(count.0));
+    let fill = f.width().map_or(0, |w| w.saturating_sub(len));
+    let (before, after) = match f.align() {
+        None | Some(fmt::Alignment::Left) => (0, fill),
+        Some(fmt::Alignment::Right) => (fill, 0),
+        Some(fmt::Alignment::Center) => (fill / 2, fill - fill / 2),
+    };
+    let c = f.fill();
+    for _ in 0..before {
+        fmt::Write::write_char(f, c)?;
+    }
+    if len < count.0 {
+        fmt::Write::write_fmt(&mut Truncate { f, left: len }, args)?;
+    } else {
+        f.write_fmt(args)?;
+    }
+    for _ in 0..after {
+        fmt::Write::write_char(f, c)?;
+    }
+    Ok(())
+}
+
+impl Pos {
+    /// Display without the `at` prefix, `1:2`
+    #[inline]
+    pub const fn bare(&self) -> BarePosDisplay {
+        BarePosDisplay(*self)
+    }
+    /// [`Pos::bare`] of the zero based pos shifted to one based, `pos(0, 0)` is `1:1`
+    #[inline]
+    pub const fn display_one_based(&self) -> BarePosDisplay {
+        one_based(*self).bare()
+    }
+}
+
+/// Shift to one based, keeping [`Pos::DUMMY`]
+const fn one_based(pos: Pos) -> Pos {
+    if pos.is_dummy() {
+        pos
+    } else {
+        ZeroBased(pos).to_one_based().0
+    }
+}
+
+impl Loc {
+    /// Display without the `at` prefix, `1:2-3:4`
+    #[inline]
+    pub const fn bare(&self) -> BareDisplay {
+        BareDisplay(*self)
+    }
+    /// [`Loc::bare`] of the zero based loc shifted to one based, `locof!(0, 0, 0, 2)` is `1:1-1:3`
+    #[inline]
+    pub const fn display_one_based(&self) -> BareDisplay {
+        Loc::new(one_based(self.from), one_based(self.to)).bare()
+    }
+    /// Display collapsing same line locs,
+    /// `1:2` if empty, `1:2-6` on one line, else `1:2-3:4`
+    #[inline]
+    pub const fn display_smart(&self) -> SmartDisplay {
+        SmartDisplay(*self)
+    }
+    /// GNU coding standards location, `file:12:5` if empty, else `file:12.5-14.8`  
+    /// Loc is zero based with an exclusive end, the output is one based with an inclusive end,
+    /// an end at column 0 is the end of the previous line and prints without a column, `file:12.5-13`
+    #[inline]
+    pub const fn display_gnu<'a>(&self, file: &'a str) -> GnuDisplay<'a> {
+        GnuDisplay { file, loc: *self }
+    }
+}
+
+impl Loc {
+    /// rustc style arrow line, ` --> file:4:9`  
+    /// Indented by the gutter width of the [`Snippet`](crate::Snippet) of the loc, output is one based
+    #[inline]
+    pub const fn display_rustc<'a>(&self, file: &'a str) -> RustcDisplay<'a> {
+        RustcDisplay { file, loc: *self }
+    }
+
+    /// [`Loc::bare`] prefixed by the file name, `src/main.rs:12:5-14:9`
+    #[inline]
+    pub const fn in_file<'a>(&self, file: &'a str) -> LocWithSource<'a> {
+        LocWithSource { file, loc: *self }
+    }
+}
+
+impl Pos {
+    /// GNU coding standards location, `file:12:5`  
+    /// Pos is zero based, the output is one based
+    #[inline]
+    pub const fn display_gnu<'a>(&self, file: &'a str) -> GnuDisplay<'a> {
+        Loc::new_same_pos(*self).display_gnu(file)
+    }
+}
+
+/// Display adapter, see [`Pos::bare`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BarePosDisplay(Pos);
+
+impl Display for BarePosDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_dummy() {
+            return f.pad(UNKNOWN);
+        }
+        pad(f, format_args!("{}:{}", self.0.line, self.0.column))
+    }
+}
+
+/// Display adapter, see [`Loc::bare`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BareDisplay(Loc);
+
+impl Display for BareDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_dummy() {
+            return f.pad(UNKNOWN);
+        }
+        pad(
+            f,
+            format_args!("{}-{}", self.0.from.bare(), self.0.to.bare()),
+        )
+    }
+}
+
+/// Display adapter, see [`Loc::display_smart`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmartDisplay(Loc);
+
+impl Display for SmartDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Loc { from, to } = self.0;
+        if from.is_dummy() || to.is_dummy() {
+            self.0.bare().fmt(f)
+        } else if from == to {
+            pad(f, format_args!("{}:{}", from.line, from.column))
+        } else if from.line == to.line {
+            pad(
+                f,
+                format_args!("{}:{}-{}", from.line, from.column, to.column),
+            )
+        } else {
+            pad(
+                f,
+                format_args!("{}:{}-{}:{}", from.line, from.column, to.line, to.column),
+            )
+        }
+    }
+}
+
+/// Display adapter, see [`Loc::display_gnu`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GnuDisplay<'a> {
+    file: &'a str,
+    loc: Loc,
+}
+
+impl Display for GnuDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Loc { from, to } = self.loc;
+        if from.is_dummy() {
+            write!(f, "{}:{}", self.file, UNKNOWN)
+        } else if from == to || to.is_dummy() {
+            write!(
+                f,
+                "{}:{}:{}",
+                self.file,
+                from.line.saturating_add(1),
+                from.column.saturating_add(1)
+            )
+        } else if to.column == 0 {
+            write!(
+                f,
+                "{}:{}.{}-{}",
+                self.file,
+                from.line.saturating_add(1),
+                from.column.saturating_add(1),
+                to.line
+            )
+        } else {
+            write!(
+                f,
+                "{}:{}.{}-{}.{}",
+                self.file,
+                from.line.saturating_add(1),
+                from.column.saturating_add(1),
+                to.line.saturating_add(1),
+                to.column
+            )
+        }
+    }
+}
+
+/// Display adapter, see [`Loc::display_rustc`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RustcDisplay<'a> {
+    file: &'a str,
+    loc: Loc,
+}
+
+/// Last line a rustc style snippet of the loc shows,
+/// an end at column 0 of a later line is the end of the previous line
+pub(crate) const fn snippet_last_line(loc: &Loc) -> usize {
+    let Loc { from, to } = *loc;
+    if from.is_dummy() {
+        0
+    } else if to.is_dummy() {
+        from.line
+    } else if to.column == 0 && to.line > from.line {
+        to.line - 1
+    } else {
+        to.line
+    }
+}
+
+/// Width of the one based line numbers in a rustc style snippet of the loc
+pub(crate) const fn gutter_width(loc: &Loc) -> usize {
+    let mut width = 1;
+    let mut last = snippet_last_line(loc).saturating_add(1);
+    while last >= 10 {
+        width += 1;
+        last /= 10;
+    }
+    width
+}
+
+impl Display for RustcDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let from = self.loc.from;
+        let gutter = gutter_width(&self.loc);
+        if from.is_dummy() {
+            return write!(
+                f,
+                "{:gutter$}--> {}:{}",
+                "",
+                self.file,
+                UNKNOWN,
+                gutter = gutter
+            );
+        }
+        write!(
+            f,
+            "{:gutter$}--> {}:{}:{}",
+            "",
+            self.file,
+            from.line.saturating_add(1),
+            from.column.saturating_add(1),
+            gutter = gutter
+        )
+    }
+}
+
+/// Display adapter, see [`Loc::in_file`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LocWithSource<'a> {
+    file: &'a str,
+    loc: Loc,
+}
+
+impl Display for LocWithSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        pad(f, format_args!("{}:{}", self.file, self.loc.bare()))
+    }
+}
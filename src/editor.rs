@@ -0,0 +1,56 @@
+use crate::Pos;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Editor URI scheme of [`editor_uri`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum EditorScheme {
+    /// `vscode://file/path:12:5`
+    VsCode,
+    /// `idea://open?file=/path&line=12&column=5`
+    JetBrains,
+    /// `file:///path#L12`
+    File,
+}
+
+/// Percent encode the path for a URI, keeping unreserved chars, `/` and `:`,
+/// backslashes become `/` on Windows
+fn encode_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    let mut out = String::with_capacity(path.len());
+    if !path.starts_with('/') && !path.starts_with('\\') {
+        out.push('/');
+    }
+    for b in path.bytes() {
+        match b {
+            b'\\' if cfg!(windows) => out.push('/'),
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                out.push(b as char)
+            }
+            _ => write!(out, "%{:02X}", b).unwrap(),
+        }
+    }
+    out
+}
+
+/// URI opening the editor at the zero based pos in the file, the URI is one based  
+/// The path should be absolute, it is percent encoded  
+/// [`Pos::DUMMY`] opens the file without a position
+pub fn editor_uri(scheme: EditorScheme, path: &Path, pos: Pos) -> String {
+    let path = encode_path(path);
+    if pos.is_dummy() {
+        return match scheme {
+            EditorScheme::VsCode => format!("vscode://file{}", path),
+            EditorScheme::JetBrains => format!("idea://open?file={}", path),
+            EditorScheme::File => format!("file://{}", path),
+        };
+    }
+    let (line, column) = (pos.line.saturating_add(1), pos.column.saturating_add(1));
+    match scheme {
+        EditorScheme::VsCode => format!("vscode://file{}:{}:{}", path, line, column),
+        EditorScheme::JetBrains => {
+            format!("idea://open?file={}&line={}&column={}", path, line, column)
+        }
+        EditorScheme::File => format!("file://{}#L{}", path, line),
+    }
+}
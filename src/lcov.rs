@@ -0,0 +1,24 @@
+use crate::LocSet;
+use core::fmt;
+
+/// Write the LCOV record of a file, a `DA` line per line with hit count 1 if covered else 0  
+/// Lines are one based in LCOV
+pub fn write_lcov<W: fmt::Write>(
+    mut w: W,
+    file: &str,
+    covered: &LocSet,
+    total_lines: usize,
+) -> fmt::Result {
+    writeln!(w, "SF:{}", file)?;
+    let mut lines = covered.covered_lines().peekable();
+    let mut hit = 0;
+    for line in 0..total_lines {
+        while lines.next_if(|&l| l < line).is_some() {}
+        let count = usize::from(lines.next_if_eq(&line).is_some());
+        hit += count;
+        writeln!(w, "DA:{},{}", line + 1, count)?;
+    }
+    writeln!(w, "LF:{}", total_lines)?;
+    writeln!(w, "LH:{}", hit)?;
+    writeln!(w, "end_of_record")
+}
@@ -0,0 +1,182 @@
+//! Fixed little endian byte layout of [`Pos`] and [`Loc`], the same on every target
+//!
+//! `Pos` is `line` then `column`, each a little endian `u64`, 16 bytes.
+//! `Loc` is `from` then `to`, 32 bytes. This is also the `borsh` encoding.  
+//! With the `zerocopy` feature [`LePos`] and [`LeLoc`] have this layout in memory,
+//! so structures holding them can be read straight out of mapped bytes.
+
+use crate::{Loc, Pos};
+
+/// Copy 8 bytes at `at` out of the array
+const fn read_u64<const N: usize>(bytes: &[u8; N], at: usize) -> u64 {
+    let mut word = [0; 8];
+    let mut i = 0;
+    while i < 8 {
+        word[i] = bytes[at + i];
+        i += 1;
+    }
+    u64::from_le_bytes(word)
+}
+
+/// Copy the little endian bytes of the value to `at` in the array
+const fn write_u64<const N: usize>(mut bytes: [u8; N], at: usize, value: u64) -> [u8; N] {
+    let word = value.to_le_bytes();
+    let mut i = 0;
+    while i < 8 {
+        bytes[at + i] = word[i];
+        i += 1;
+    }
+    bytes
+}
+
+impl Pos {
+    /// The 16 byte little endian layout, see the [module docs](crate::le_bytes)
+    pub const fn to_le_bytes(&self) -> [u8; 16] {
+        let bytes = write_u64([0; 16], 0, self.line as u64);
+        write_u64(bytes, 8, self.column as u64)
+    }
+
+    /// From the 16 byte little endian layout, fields are truncated on 32 bit targets
+    pub const fn from_le_bytes(bytes: [u8; 16]) -> Pos {
+        Pos::new(read_u64(&bytes, 0) as usize, read_u64(&bytes, 8) as usize)
+    }
+}
+
+impl Loc {
+    /// The 32 byte little endian layout, see the [module docs](crate::le_bytes)
+    pub const fn to_le_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0; 32];
+        let values = [
+            self.from.line,
+            self.from.column,
+            self.to.line,
+            self.to.column,
+        ];
+        let mut i = 0;
+        while i < 4 {
+            bytes = write_u64(bytes, i * 8, values[i] as u64);
+            i += 1;
+        }
+        bytes
+    }
+
+    /// From the 32 byte little endian layout, fields are truncated on 32 bit targets
+    pub const fn from_le_bytes(bytes: [u8; 32]) -> Loc {
+        Loc::new_at(
+            read_u64(&bytes, 0) as usize,
+            read_u64(&bytes, 8) as usize,
+            read_u64(&bytes, 16) as usize,
+            read_u64(&bytes, 24) as usize,
+        )
+    }
+
+    /// [`Loc::from_le_bytes`], `None` if `from` is after `to` or a field is out of `usize`  
+    /// [`Loc::DUMMY`] is accepted
+    pub fn try_from_le_bytes(bytes: [u8; 32]) -> Option<Loc> {
+        use core::convert::TryFrom;
+
+        let field = |at| usize::try_from(read_u64(&bytes, at)).ok();
+        let loc = Loc::new_at(field(0)?, field(8)?, field(16)?, field(24)?);
+        if loc.from <= loc.to || loc.is_dummy() {
+            Some(loc)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(feature = "zerocopy")]
+pub use self::zc::*;
+
+#[cfg(feature = "zerocopy")]
+mod zc {
+    use crate::{Loc, Pos};
+    use core::convert::TryFrom;
+    use zerocopy::byteorder::little_endian::U64;
+    use zerocopy::{FromBytes, Immutable, IntoBytes, KnownLayout, Unaligned};
+
+    /// [`Pos`] in the little endian layout, unaligned
+    #[derive(
+        Debug,
+        PartialEq,
+        Eq,
+        Clone,
+        Copy,
+        Hash,
+        FromBytes,
+        IntoBytes,
+        Unaligned,
+        KnownLayout,
+        Immutable,
+    )]
+    #[repr(C)]
+    pub struct LePos {
+        /// nth of line
+        pub line: U64,
+        /// nth of characters in current line
+        pub column: U64,
+    }
+
+    /// [`Loc`] in the little endian layout, unaligned  
+    /// Bytes may hold a reversed loc, [`Loc::try_from`] checks it
+    #[derive(
+        Debug,
+        PartialEq,
+        Eq,
+        Clone,
+        Copy,
+        Hash,
+        FromBytes,
+        IntoBytes,
+        Unaligned,
+        KnownLayout,
+        Immutable,
+    )]
+    #[repr(C)]
+    pub struct LeLoc {
+        /// from
+        pub from: LePos,
+        /// to
+        pub to: LePos,
+    }
+
+    impl From<Pos> for LePos {
+        #[inline]
+        fn from(pos: Pos) -> Self {
+            LePos {
+                line: U64::new(pos.line as u64),
+                column: U64::new(pos.column as u64),
+            }
+        }
+    }
+
+    impl From<LePos> for Pos {
+        /// Fields are truncated on 32 bit targets
+        #[inline]
+        fn from(pos: LePos) -> Self {
+            Pos::new(pos.line.get() as usize, pos.column.get() as usize)
+        }
+    }
+
+    impl From<Loc> for LeLoc {
+        #[inline]
+        fn from(loc: Loc) -> Self {
+            LeLoc {
+                from: loc.from.into(),
+                to: loc.to.into(),
+            }
+        }
+    }
+
+    impl TryFrom<LeLoc> for Loc {
+        type Error = LeLoc;
+
+        /// Checked as [`Loc::try_from_le_bytes`], errors with the input
+        #[inline]
+        fn try_from(loc: LeLoc) -> Result<Self, LeLoc> {
+            let mut bytes = [0; 32];
+            bytes.copy_from_slice(loc.as_bytes());
+            Loc::try_from_le_bytes(bytes).ok_or(loc)
+        }
+    }
+}
@@ -1,56 +1,238 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![allow(clippy::from_over_into)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 #[cfg(test)]
 mod tests;
 
+#[macro_use]
+mod common;
+
+pub mod prelude;
+
+#[cfg(feature = "alloc")]
+mod line_index;
+#[cfg(feature = "alloc")]
+pub use line_index::*;
+
+mod spanned;
+pub use spanned::*;
+
+mod display;
+pub use display::*;
+
+mod source_location;
+pub use source_location::*;
+
+mod based;
+pub use based::*;
+
+mod source;
+#[cfg(feature = "unicode-width")]
+pub use source::visual_column;
+pub use source::PosCursor;
+
+mod parse;
+pub use parse::*;
+
+mod assert;
+pub use assert::*;
+
+#[cfg(feature = "alloc")]
+mod spans;
+#[cfg(feature = "alloc")]
+pub use spans::*;
+
+#[cfg(feature = "alloc")]
+mod lcov;
+#[cfg(feature = "alloc")]
+pub use lcov::*;
+
+#[cfg(feature = "alloc")]
+mod source_text;
+#[cfg(feature = "alloc")]
+pub use source_text::*;
+
+#[cfg(feature = "alloc")]
+mod snippet;
+#[cfg(feature = "alloc")]
+pub use snippet::*;
+
+#[cfg(feature = "alloc")]
+pub mod delta;
+
+#[cfg(feature = "alloc")]
+mod semantic_tokens;
+#[cfg(feature = "alloc")]
+pub use semantic_tokens::*;
+
+mod link;
+pub use link::*;
+
+mod by_start;
+pub use by_start::*;
+
+mod small;
+pub use small::*;
+
+mod opt;
+pub use opt::*;
+
+mod relation;
+pub use relation::*;
+
+mod compact_loc;
+pub use compact_loc::*;
+
+pub mod le_bytes;
+
+#[cfg(feature = "alloc")]
+mod diff_remap;
+#[cfg(feature = "alloc")]
+pub use diff_remap::*;
+
+#[cfg(feature = "alloc")]
+mod annotation;
+#[cfg(feature = "alloc")]
+pub use annotation::*;
+
+#[cfg(feature = "alloc")]
+mod quickfix;
+#[cfg(feature = "alloc")]
+pub use quickfix::*;
+
+#[cfg(feature = "std")]
+mod editor;
+#[cfg(feature = "std")]
+pub use editor::*;
+
+#[cfg(feature = "codespan")]
+mod codespan_impl;
+
+#[cfg(feature = "defmt")]
+mod defmt_impl;
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impl;
+
+#[cfg(feature = "annotate-snippets")]
+mod annotate_snippets_impl;
+
+#[cfg(feature = "text-size")]
+mod text_size_impl;
+
+#[cfg(feature = "ansi")]
+mod ansi;
+#[cfg(feature = "ansi")]
+pub use ansi::*;
+
+#[cfg(feature = "serde")]
+pub mod serde_any;
+#[cfg(feature = "serde")]
+pub mod serde_compact;
+#[cfg(feature = "serde")]
+pub mod serde_strict;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub mod spanned_flat;
+#[cfg(feature = "serde")]
+pub mod spanned_value_only;
+
+#[cfg(feature = "serde")]
+mod sarif;
+#[cfg(feature = "serde")]
+pub use sarif::*;
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+mod diagnostic;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub use diagnostic::*;
+
+#[cfg(feature = "lsp")]
+mod lsp;
+#[cfg(feature = "lsp")]
+pub use lsp::*;
+
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impl;
+
+#[cfg(feature = "rkyv")]
+mod rkyv_impl;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "bincode")]
+use bincode::{Decode, Encode};
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+
 use core::clone::Clone;
 use core::cmp::{Eq, Ord, PartialEq, PartialOrd};
 use core::convert::{From, Into};
 use core::default::Default;
 use core::fmt;
-use core::fmt::{Debug, Display};
+use core::fmt::Display;
 use core::hash::Hash;
 use core::marker::Copy;
-use core::ops::{Range, RangeFrom, RangeTo};
+use core::ops::{Range, RangeFrom, RangeFull, RangeTo};
 
 /// Posation in source code
+///
+/// With `borsh` it is encoded as `line` then `column`, each a little-endian u64, 16 bytes total
+///
+/// # Stability
+/// The fields stay public and the struct stays exhaustive for all of 1.x,
+/// [`Pos::new`], [`Pos::line`] and [`Pos::column`] work the same if that ever changes
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "bincode", derive(Encode, Decode))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(Archive, RkyvSerialize, RkyvDeserialize),
+    archive(check_bytes)
+)]
+#[cfg_attr(not(feature = "compact-debug"), derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+#[repr(C)]
 pub struct Pos {
     /// nth of line
+    #[cfg_attr(feature = "serde", serde(alias = "row"))]
     pub line: usize,
     /// nth of characters in current line
+    #[cfg_attr(feature = "serde", serde(alias = "col"))]
     pub column: usize,
 }
 impl Pos {
-    /// New zero
+    /// Stable 64 bit fingerprint, 64 bit FNV-1a over `line`, `column`,
+    /// each as 8 little endian bytes, see [`Loc::fingerprint`]
     #[inline]
-    pub const fn zero() -> Self {
-        Self::new_same(0)
+    pub const fn fingerprint(&self) -> u64 {
+        fnv1a(FNV_OFFSET, &[self.line, self.column])
     }
-    /// New at
+    /// New from `u32`
     #[inline]
-    pub const fn new(line: usize, column: usize) -> Self {
-        Self { line, column }
+    pub const fn from_u32(line: u32, column: u32) -> Self {
+        Self::new(line as usize, column as usize)
     }
-    /// New same value
+    /// New from `u16`
     #[inline]
-    pub const fn new_same(value: usize) -> Self {
-        Self::new(value, value)
+    pub const fn from_u16(line: u16, column: u16) -> Self {
+        Self::new(line as usize, column as usize)
     }
 }
 
 impl Display for Pos {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "at {}:{}", self.line, self.column)
-    }
-}
-
-impl Default for Pos {
-    #[inline(always)]
-    fn default() -> Self {
-        Self::zero()
+        if self.is_dummy() {
+            return f.pad(UNKNOWN);
+        }
+        pad(f, format_args!("at {}", self.bare()))
     }
 }
 
@@ -84,6 +266,9 @@ impl From<usize> for Pos {
         Self::new_same(value)
     }
 }
+/// Deprecated, drops the column, use [`Pos::line`]  
+/// Only with the `legacy-conversions` feature, which goes away in the next major release
+#[cfg(feature = "legacy-conversions")]
 impl Into<usize> for Pos {
     #[inline]
     fn into(self) -> usize {
@@ -96,6 +281,9 @@ impl From<[usize; 1]> for Pos {
         Self::new_same(value)
     }
 }
+/// Deprecated, drops the column, use `[pos.line()]`  
+/// Only with the `legacy-conversions` feature, which goes away in the next major release
+#[cfg(feature = "legacy-conversions")]
 impl Into<[usize; 1]> for Pos {
     #[inline]
     fn into(self) -> [usize; 1] {
@@ -130,11 +318,24 @@ pub const fn pos(line: usize, column: usize) -> Pos {
 /// assert_eq!(a, b);
 /// assert_eq!(b, c);
 /// ```
+/// The two argument, literal array and literal tuple forms are const
+/// ```
+/// # use srcpos::*;
+/// const A: Pos = posof!(1, 2);
+/// const B: Pos = posof!([1, 2]);
+/// const C: Pos = posof!((1, 2));
+/// ```
 #[macro_export]
 macro_rules! posof {
     ($a:expr, $b:expr) => {
         $crate::pos($a, $b)
     };
+    ([$a:expr, $b:expr]) => {
+        $crate::pos($a, $b)
+    };
+    (($a:expr, $b:expr)) => {
+        $crate::pos($a, $b)
+    };
     ($a:expr) => {
         $crate::Pos::from($a)
     };
@@ -143,65 +344,88 @@ macro_rules! posof {
 //\/////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// Range of Posation in source code
+///
+/// With `borsh` it is encoded as `from` then `to`, 32 bytes total
+///
+/// # Stability
+/// The fields stay public and the struct stays exhaustive for all of 1.x,
+/// [`Loc::new`], [`Loc::start`] and [`Loc::end`] work the same if that ever changes
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "bincode", derive(Encode, Decode))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(Archive, RkyvSerialize, RkyvDeserialize),
+    archive(check_bytes)
+)]
+#[cfg_attr(not(feature = "compact-debug"), derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+#[repr(C)]
 pub struct Loc {
     /// from
+    #[cfg_attr(feature = "serde", serde(alias = "start"))]
     pub from: Pos,
     /// to
+    #[cfg_attr(feature = "serde", serde(alias = "end"))]
     pub to: Pos,
 }
+pos_loc_common!(Pos, Loc, usize);
+
 impl Loc {
-    /// New at
-    #[inline]
-    pub const fn new(from: Pos, to: Pos) -> Self {
-        Self { from, to }
-    }
-    /// New at
+    /// New at from `u32`
     #[inline]
-    pub const fn new_at(
-        from_line: usize,
-        from_column: usize,
-        to_line: usize,
-        to_column: usize,
-    ) -> Self {
+    pub const fn from_u32(from_line: u32, from_column: u32, to_line: u32, to_column: u32) -> Self {
         Self::new(
-            Pos::new(from_line, from_column),
-            Pos::new(to_line, to_column),
+            Pos::from_u32(from_line, from_column),
+            Pos::from_u32(to_line, to_column),
         )
     }
-    /// New zero
-    #[inline]
-    pub const fn zero() -> Self {
-        Self::new_same_pos(Pos::zero())
-    }
-    /// New same value
-    #[inline]
-    pub const fn new_same(value: usize) -> Self {
-        Self::new_same_pos(Pos::new_same(value))
-    }
-    /// New same value
-    #[inline]
-    pub const fn new_same_pos(pos: Pos) -> Self {
-        Self::new(pos, pos)
+    /// Stable 64 bit fingerprint, independent of the std hasher and of the crate version  
+    /// 64 bit FNV-1a over `from.line`, `from.column`, `to.line`, `to.column`,
+    /// each as 8 little endian bytes
+    #[inline]
+    pub const fn fingerprint(&self) -> u64 {
+        fnv1a(
+            FNV_OFFSET,
+            &[
+                self.from.line,
+                self.from.column,
+                self.to.line,
+                self.to.column,
+            ],
+        )
     }
 }
 
 impl Display for Loc {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(
+        if self.is_dummy() {
+            return f.pad(UNKNOWN);
+        }
+        pad(
             f,
-            "at {}:{} to {}:{}",
-            self.from.line, self.from.column, self.to.line, self.to.column,
+            format_args!("at {} to {}", self.from.bare(), self.to.bare()),
         )
     }
 }
 
-impl Default for Loc {
-    #[inline(always)]
-    fn default() -> Self {
-        Self::zero()
-    }
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64 bit FNV-1a of the values as `u64` little endian bytes
+const fn fnv1a(mut hash: u64, values: &[usize]) -> u64 {
+    let mut i = 0;
+    while i < values.len() {
+        let bytes = (values[i] as u64).to_le_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            hash ^= bytes[j] as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+            j += 1;
+        }
+        i += 1;
+    }
+    hash
 }
 
 // #region From Into Basic Misc
@@ -256,6 +480,9 @@ impl Into<[(usize, usize); 2]> for Loc {
         ]
     }
 }
+/// Deprecated, drops `to`, use `loc.from.into()` or [`Loc::to_tuple`]  
+/// Only with the `legacy-conversions` feature, which goes away in the next major release
+#[cfg(feature = "legacy-conversions")]
 impl Into<[usize; 2]> for Loc {
     #[inline]
     fn into(self) -> [usize; 2] {
@@ -268,12 +495,18 @@ impl From<usize> for Loc {
         Self::new_same(value)
     }
 }
+/// Deprecated, drops all but `from.line`, use [`Loc::start_line`]  
+/// Only with the `legacy-conversions` feature, which goes away in the next major release
+#[cfg(feature = "legacy-conversions")]
 impl Into<usize> for Loc {
     #[inline]
     fn into(self) -> usize {
         self.from.line
     }
 }
+/// Deprecated, drops all but `from.line`, use `[loc.start_line()]`  
+/// Only with the `legacy-conversions` feature, which goes away in the next major release
+#[cfg(feature = "legacy-conversions")]
 impl Into<[usize; 1]> for Loc {
     #[inline]
     fn into(self) -> [usize; 1] {
@@ -319,12 +552,26 @@ impl<T: From<Pos>> Into<RangeTo<T>> for Loc {
         ..self.to.into()
     }
 }
+/// The end is [`Pos::DUMMY`], the max pos
+impl<T: Into<Pos>> From<RangeFrom<T>> for Loc {
+    #[inline]
+    fn from(r: RangeFrom<T>) -> Self {
+        Self::new(r.start.into(), Pos::DUMMY)
+    }
+}
 impl<T: From<Pos>> Into<RangeFrom<T>> for Loc {
     #[inline]
     fn into(self) -> RangeFrom<T> {
         self.from.into()..
     }
 }
+/// From zero to [`Pos::DUMMY`], the max pos
+impl From<RangeFull> for Loc {
+    #[inline]
+    fn from(_: RangeFull) -> Self {
+        Self::new(Pos::zero(), Pos::DUMMY)
+    }
+}
 // #endregion
 
 // #region From Into Misc
@@ -393,18 +640,43 @@ pub const fn loc(from: Pos, to: Pos) -> Loc {
 /// let c = locof!(1, 2, 3, 4);
 /// let d = locof!(pos(1, 2)..pos(3, 4));
 /// let e = locof![1, 2, 3, 4];
+/// let f = locof!((1, 2), [3, 4]);
+/// let g = locof!((1, 2)..(3, 4));
 /// assert_eq!(a, b);
 /// assert_eq!(b, c);
 /// assert_eq!(c, d);
 /// assert_eq!(d, e);
+/// assert_eq!(e, f);
+/// assert_eq!(f, g);
+/// ```
+/// Open ranges reach [`Pos::zero`] and [`Pos::DUMMY`]
+/// ```
+/// # use srcpos::*;
+/// assert_eq!(locof!(..(3, 4)), locof!(Pos::zero(), pos(3, 4)));
+/// assert_eq!(locof!((1, 2)..), locof!(pos(1, 2), Pos::DUMMY));
+/// assert_eq!(locof!(..), locof!(Pos::zero(), Pos::DUMMY));
+/// ```
+/// The four argument, literal array pair and literal tuple pair forms are const,
+/// the others go through [`Into`] and are not
+/// ```
+/// # use srcpos::*;
+/// const A: Loc = locof!(1, 2, 3, 4);
+/// const B: Loc = locof!([1, 2], [3, 4]);
+/// static C: Loc = locof!((1, 2), (3, 4));
 /// ```
 #[macro_export]
 macro_rules! locof {
+    ([$a:expr, $b:expr], [$c:expr, $d:expr]) => {
+        $crate::Loc::new_at($a, $b, $c, $d)
+    };
+    (($a:expr, $b:expr), ($c:expr, $d:expr)) => {
+        $crate::Loc::new_at($a, $b, $c, $d)
+    };
     ($from:expr, $to:expr) => {
         $crate::loc($from.into(), $to.into())
     };
     ($a:expr, $b:expr, $c:expr, $d:expr) => {
-        $crate::loc(pos($a, $b), pos($c, $d))
+        $crate::Loc::new_at($a, $b, $c, $d)
     };
     ($v:expr) => {
         $crate::Loc::from($v)
@@ -423,9 +695,152 @@ macro_rules! locof {
 /// let current_pos = pos!();
 /// println!("defined on pos: {}", current_pos);
 /// ```
+/// `pos!(delta_lines, delta_columns)` shifts the result by a signed offset, saturating at 0
+/// ```rust
+/// # use srcpos::*;
+/// let above = pos!(-1, 0);
+/// assert_eq!(above.line, line!() as usize - 2);
+/// ```
 #[macro_export]
 macro_rules! pos {
     () => {
         $crate::pos(line!() as usize, column!() as usize)
     };
+    ($delta_lines:expr, $delta_columns:expr $(,)?) => {
+        $crate::pos(
+            $crate::__offset(line!() as usize, $delta_lines),
+            $crate::__offset(column!() as usize, $delta_columns),
+        )
+    };
+}
+
+/// Zero based [`pos!`], `line!() - 1` and `column!() - 1` saturating at 0
+/// # Examples
+/// ```rust
+/// # use srcpos::*;
+/// let p = pos0!();
+/// assert_eq!(p, pos(pos!().line - 2, 8));
+/// ```
+#[macro_export]
+macro_rules! pos0 {
+    () => {
+        $crate::pos!(-1, -1)
+    };
+}
+
+/// Expands to the loc on which it was invoked.  
+/// `loc!()` is the zero width loc at [`pos!`],
+/// `loc!(expr)` evaluates the expr into a [`Spanned`] from [`pos!`] to the end of the invocation.
+///
+/// The end is approximated by the [`stringify!`]ed invocation, which is on one line
+/// and has normalized whitespace, so it is exact only for single line invocations written in that style.
+/// # Examples
+/// ```rust
+/// # use srcpos::*;
+/// let here = loc!();
+/// let value = loc!(1 + 2);
+/// assert_eq!(value.value, 3);
+/// assert_eq!(value.loc.to.column - value.loc.from.column, "loc!(1 + 2)".len());
+/// ```
+#[macro_export]
+macro_rules! loc {
+    () => {
+        $crate::Loc::new_same_pos($crate::pos!())
+    };
+    ($e:expr) => {{
+        let from = $crate::pos!();
+        let width = $crate::__char_count(concat!("loc!(", stringify!($e), ")"));
+        $crate::Spanned::new(
+            $e,
+            $crate::loc(from, $crate::pos(from.line, from.column + width)),
+        )
+    }};
+}
+
+#[doc(hidden)]
+pub const fn __offset(base: usize, delta: isize) -> usize {
+    if delta < 0 {
+        base.saturating_sub(delta.unsigned_abs())
+    } else {
+        base.saturating_add(delta as usize)
+    }
+}
+
+#[doc(hidden)]
+pub const fn __char_count(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] & 0xC0 != 0x80 {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
+
+/// Build Spanned  
+/// `spanned!(value)` is at the zero width [`loc!`] of the invocation,
+/// `spanned!(value, loc)` pairs them and works in const
+/// # Examples
+/// ```rust
+/// # use srcpos::*;
+/// const A: Spanned<u8> = spanned!(1, Loc::new_at(1, 2, 3, 4));
+/// let b = spanned!(1);
+/// assert_eq!(A.value, b.value);
+/// assert_eq!(b.loc, Loc::from(pos(line!() as usize - 2, 9)));
+/// ```
+#[macro_export]
+macro_rules! spanned {
+    ($value:expr) => {
+        $crate::Spanned::new($value, $crate::loc!())
+    };
+    ($value:expr, $loc:expr) => {
+        $crate::Spanned::new($value, $loc)
+    };
+}
+
+/// Like [`dbg!`](https://doc.rust-lang.org/std/macro.dbg.html), but tagged with a pos.  
+/// `dbg_loc!(expr)` prints the invocation [`pos!`], `dbg_loc!(expr, loc)` prints the given loc instead.
+/// Returns the value of expr.
+/// # Examples
+/// ```rust
+/// # use srcpos::*;
+/// let a = dbg_loc!(1 + 2) * 2;
+/// let token = dbg_loc!("fn", locof!(0, 0, 0, 2));
+/// # assert_eq!((a, token), (6, "fn"));
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! dbg_loc {
+    ($e:expr $(,)?) => {
+        match $e {
+            tmp => {
+                ::std::eprintln!(
+                    "[{}:{}:{}] {} = {:#?}",
+                    file!(),
+                    line!(),
+                    column!(),
+                    stringify!($e),
+                    &tmp
+                );
+                tmp
+            }
+        }
+    };
+    ($e:expr, $loc:expr $(,)?) => {
+        match $e {
+            tmp => {
+                ::std::eprintln!(
+                    "[{}:{}] {} = {:#?}",
+                    file!(),
+                    $crate::Loc::from($loc).display_smart(),
+                    stringify!($e),
+                    &tmp
+                );
+                tmp
+            }
+        }
+    };
 }
@@ -0,0 +1,675 @@
+use crate::{Loc, Pos};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// Unit of columns in other protocols, as LSP `PositionEncodingKind`  
+/// `Pos` columns are always chars, the same as `Utf32`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum PositionEncoding {
+    /// bytes
+    Utf8,
+    /// UTF-16 code units, the LSP default
+    Utf16,
+    /// chars
+    Utf32,
+}
+
+/// Line table of a source text  
+/// Maps byte offsets to zero-based `Pos`, columns are counted in chars
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct LineIndex {
+    /// byte offset of each line start
+    lines: Vec<usize>,
+    /// byte offset and utf8 len of each non-ascii char
+    wide: Vec<(usize, u8)>,
+    /// byte len of the text
+    len: usize,
+}
+impl LineIndex {
+    /// Build from text  
+    /// With the `fast` feature line breaks are found with `memchr`
+    #[inline]
+    pub fn new(text: &str) -> Self {
+        #[cfg(feature = "fast")]
+        return Self::new_fast(text);
+        #[cfg(not(feature = "fast"))]
+        return Self::new_scalar(text);
+    }
+
+    /// [`LineIndex::new`] with `memchr` for `\n` and a byte scan for non-ascii lead bytes
+    #[cfg(feature = "fast")]
+    fn new_fast(text: &str) -> Self {
+        let bytes = text.as_bytes();
+        let mut lines = vec![0];
+        lines.extend(memchr::memchr_iter(b'\n', bytes).map(|i| i + 1));
+        let mut wide = Vec::new();
+        let mut i = if text.is_ascii() { bytes.len() } else { 0 };
+        while i < bytes.len() {
+            // skip ascii eight bytes at a time
+            if let Some(chunk) = bytes.get(i..i + 8) {
+                let mut word = [0; 8];
+                word.copy_from_slice(chunk);
+                if u64::from_ne_bytes(word) & 0x8080_8080_8080_8080 == 0 {
+                    i += 8;
+                    continue;
+                }
+            }
+            // the text is utf8, so a non-ascii byte here is a lead byte
+            let len = bytes[i].leading_ones() as usize;
+            if len > 0 {
+                wide.push((i, len as u8));
+                i += len;
+            } else {
+                i += 1;
+            }
+        }
+        Self {
+            lines,
+            wide,
+            len: text.len(),
+        }
+    }
+
+    /// [`LineIndex::new`] scanning chunks of the text on the rayon thread pool,
+    /// the same index as the sequential build  
+    /// Chunks split on char boundaries, only `\n` starts a line so a CRLF pair split by a chunk needs no care
+    #[cfg(feature = "rayon")]
+    pub fn new_parallel(text: &str) -> Self {
+        use rayon::prelude::*;
+
+        const MIN_CHUNK: usize = 1 << 16;
+        let chunk = (text.len() / rayon::current_num_threads()).max(MIN_CHUNK);
+        if chunk >= text.len() {
+            return Self::new(text);
+        }
+        let mut bounds = vec![0];
+        while let Some(&last) = bounds.last() {
+            if last == text.len() {
+                break;
+            }
+            let mut end = (last + chunk).min(text.len());
+            while !text.is_char_boundary(end) {
+                end += 1;
+            }
+            bounds.push(end);
+        }
+        let parts: Vec<Self> = bounds
+            .par_windows(2)
+            .map(|w| Self::new(&text[w[0]..w[1]]))
+            .collect();
+        let mut index = Self {
+            lines: vec![0],
+            wide: Vec::new(),
+            len: text.len(),
+        };
+        for (part, &start) in parts.into_iter().zip(&bounds) {
+            index
+                .lines
+                .extend(part.lines[1..].iter().map(|at| at + start));
+            index
+                .wide
+                .extend(part.wide.iter().map(|&(at, len)| (at + start, len)));
+        }
+        index
+    }
+
+    /// Index every text on the rayon thread pool, the indices in the order of the texts  
+    /// Each is the same as [`LineIndex::new`], large texts are split as in [`LineIndex::new_parallel`]
+    #[cfg(feature = "rayon")]
+    pub fn index_all_parallel<T: AsRef<str> + Sync>(texts: &[T]) -> Vec<Self> {
+        use rayon::prelude::*;
+
+        texts
+            .par_iter()
+            .map(|text| Self::new_parallel(text.as_ref()))
+            .collect()
+    }
+
+    /// [`LineIndex::new`] as a plain char loop, kept for comparing against the `fast` path
+    #[cfg(any(test, not(feature = "fast")))]
+    pub(crate) fn new_scalar(text: &str) -> Self {
+        let mut lines = vec![0];
+        let mut wide = Vec::new();
+        for (i, c) in text.char_indices() {
+            if c == '\n' {
+                lines.push(i + 1);
+            } else if !c.is_ascii() {
+                wide.push((i, c.len_utf8() as u8));
+            }
+        }
+        Self {
+            lines,
+            wide,
+            len: text.len(),
+        }
+    }
+
+    /// Update in place after replacing the byte range of the text with new_text,
+    /// same as rebuilding from the edited text
+    /// # Panics
+    /// If the range is reversed or out of the text
+    pub fn apply_edit(&mut self, range: Range<usize>, new_text: &str) {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "edit range out of the text"
+        );
+        let (start, end) = (range.start, range.end);
+        let shift = |at: usize| at - end + start + new_text.len();
+
+        let mut lines = Vec::new();
+        let mut wide = Vec::new();
+        for (i, c) in new_text.char_indices() {
+            if c == '\n' {
+                lines.push(start + i + 1);
+            } else if !c.is_ascii() {
+                wide.push((start + i, c.len_utf8() as u8));
+            }
+        }
+
+        // line starts after a removed `\n` are in `start + 1..=end`
+        let lo = self.lines.partition_point(|&at| at <= start);
+        let hi = self.lines.partition_point(|&at| at <= end);
+        for at in &mut self.lines[hi..] {
+            *at = shift(*at);
+        }
+        self.lines.splice(lo..hi, lines);
+
+        let lo = self.wide.partition_point(|&(at, _)| at < start);
+        let hi = self.wide.partition_point(|&(at, _)| at < end);
+        for (at, _) in &mut self.wide[hi..] {
+            *at = shift(*at);
+        }
+        self.wide.splice(lo..hi, wide);
+
+        self.len = shift(self.len);
+    }
+
+    /// [`LineIndex::apply_edit`] for a batch of edits with byte ranges of the unedited text,
+    /// applied from the last to the first so no range needs shifting  
+    /// Edits at the same offset apply inserts first, equal ranges in the given order
+    /// # Panics
+    /// If two edits overlap or a range is reversed or out of the text
+    pub fn apply_edits<'a>(&mut self, edits: impl IntoIterator<Item = (Range<usize>, &'a str)>) {
+        let mut edits: Vec<_> = edits.into_iter().collect();
+        edits.sort_by_key(|(range, _)| (range.start, range.end));
+        assert!(
+            edits.windows(2).all(|w| w[0].0.end <= w[1].0.start),
+            "overlapping edits"
+        );
+        for (range, new_text) in edits.into_iter().rev() {
+            self.apply_edit(range, new_text);
+        }
+    }
+
+    /// Byte len of the text
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Is the text empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Count of lines, a text always has at least one line
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Byte offset of the line start
+    #[inline]
+    pub fn line_start(&self, line: usize) -> Option<usize> {
+        self.lines.get(line).copied()
+    }
+
+    /// Byte range of the line, without the `\n`
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = self.line_start(line)?;
+        let end = match self.line_start(line + 1) {
+            Some(next) => next - 1,
+            None => self.len,
+        };
+        Some(start..end)
+    }
+
+    /// Char len of the line, without the `\n`
+    pub fn line_len(&self, line: usize) -> Option<usize> {
+        let range = self.line_range(line)?;
+        let extra: usize = self
+            .wide_in(range.clone())
+            .iter()
+            .map(|&(_, len)| len as usize - 1)
+            .sum();
+        Some(range.len() - extra)
+    }
+
+    /// Byte len of the line, without the `\n`
+    #[inline]
+    pub fn line_len_bytes(&self, line: usize) -> Option<usize> {
+        self.line_range(line).map(|range| range.len())
+    }
+
+    /// Non-ascii chars in the byte range
+    fn wide_in(&self, range: Range<usize>) -> &[(usize, u8)] {
+        let lo = self.wide.partition_point(|&(at, _)| at < range.start);
+        let hi = self.wide.partition_point(|&(at, _)| at < range.end);
+        &self.wide[lo..hi]
+    }
+
+    /// Line containing the byte offset
+    fn line_of(&self, offset: usize) -> usize {
+        self.lines.partition_point(|&start| start <= offset) - 1
+    }
+
+    /// Pos of the byte offset  
+    /// `None` if out of the text or not on a char boundary
+    pub fn pos_of(&self, offset: usize) -> Option<Pos> {
+        if offset > self.len {
+            return None;
+        }
+        let line = self.line_of(offset);
+        let start = self.lines[line];
+        let mut column = offset - start;
+        for &(at, len) in self.wide_in(start..offset) {
+            if offset < at + len as usize {
+                return None;
+            }
+            column -= len as usize - 1;
+        }
+        Some(Pos::new(line, column))
+    }
+
+    /// Pos of the byte offset, forgiving version of [`LineIndex::pos_of`]  
+    /// Clamps to the text and rounds to the nearest char boundary, down on ties
+    pub fn nearest_pos(&self, offset: usize) -> Pos {
+        let mut offset = offset.min(self.len);
+        let i = self.wide.partition_point(|&(at, _)| at < offset);
+        if let Some(&(at, len)) = i.checked_sub(1).map(|i| &self.wide[i]) {
+            let end = at + len as usize;
+            if offset < end {
+                offset = if offset - at <= end - offset { at } else { end };
+            }
+        }
+        self.pos_of(offset).unwrap_or_else(|| self.end_pos())
+    }
+
+    /// Byte offset of the pos  
+    /// `None` if the line does not exist or the column is past the line end
+    pub fn offset_of(&self, pos: Pos) -> Option<usize> {
+        let range = self.line_range(pos.line)?;
+        let mut offset = range.start.checked_add(pos.column)?;
+        for &(at, len) in self.wide_in(range.clone()) {
+            if at >= offset {
+                break;
+            }
+            offset = offset.checked_add(len as usize - 1)?;
+        }
+        if offset <= range.end {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+
+    /// Pos of the end of the text
+    pub fn end_pos(&self) -> Pos {
+        let line = self.lines.len() - 1;
+        let start = self.lines[line];
+        let extra: usize = self
+            .wide_in(start..self.len)
+            .iter()
+            .map(|&(_, len)| len as usize - 1)
+            .sum();
+        Pos::new(line, self.len - start - extra)
+    }
+
+    /// Column of the pos in the encoding  
+    /// `None` if the line does not exist or the column is past the line end
+    pub fn encode_column(&self, pos: Pos, encoding: PositionEncoding) -> Option<usize> {
+        let offset = self.offset_of(pos)?;
+        Some(match encoding {
+            PositionEncoding::Utf8 => offset - self.lines[pos.line],
+            PositionEncoding::Utf16 => {
+                let astral = self.wide_in(self.lines[pos.line]..offset);
+                pos.column + astral.iter().filter(|&&(_, len)| len == 4).count()
+            }
+            PositionEncoding::Utf32 => pos.column,
+        })
+    }
+
+    /// Pos of the column in the encoding on the line  
+    /// `None` if the line does not exist, the column is past the line end or inside a char
+    pub fn decode_column(
+        &self,
+        line: usize,
+        column: usize,
+        encoding: PositionEncoding,
+    ) -> Option<Pos> {
+        let range = self.line_range(line)?;
+        let pos = match encoding {
+            PositionEncoding::Utf8 => self.pos_of(range.start.checked_add(column)?)?,
+            PositionEncoding::Utf16 => {
+                let mut column_chars = column;
+                let mut astral = 0;
+                for &(at, len) in self.wide_in(range.clone()) {
+                    if len != 4 {
+                        continue;
+                    }
+                    let at = self.pos_of(at)?.column + astral;
+                    if at >= column {
+                        break;
+                    }
+                    if at + 1 == column {
+                        return None;
+                    }
+                    astral += 1;
+                    column_chars -= 1;
+                }
+                Pos::new(line, column_chars)
+            }
+            PositionEncoding::Utf32 => Pos::new(line, column),
+        };
+        if pos.line == line && self.offset_of(pos)? <= range.end {
+            Some(pos)
+        } else {
+            None
+        }
+    }
+
+    /// Cursor for many [`LineIndex::pos_of`] lookups, fast when they are sorted or clustered
+    #[inline]
+    pub fn cursor(&self) -> LineIndexCursor<'_> {
+        LineIndexCursor {
+            index: self,
+            line: 0,
+            wide: 0,
+        }
+    }
+
+    /// [`LineIndex::pos_of`] of each sorted offset in one pass over the lines,
+    /// O(lines + offsets) instead of a binary search each
+    /// # Panics
+    /// In debug builds if the offsets are not sorted
+    pub fn positions_of_sorted(&self, offsets: &[usize]) -> Vec<Option<Pos>> {
+        debug_assert!(
+            offsets.windows(2).all(|w| w[0] <= w[1]),
+            "offsets are not sorted"
+        );
+        let mut cursor = self.cursor();
+        offsets.iter().map(|&o| cursor.pos_of_forward(o)).collect()
+    }
+
+    /// Lazy [`LineIndex::positions_of_sorted`]
+    /// # Panics
+    /// In debug builds when reaching an offset smaller than the one before
+    pub fn positions_of_sorted_iter<'a>(
+        &'a self,
+        offsets: impl IntoIterator<Item = usize> + 'a,
+    ) -> impl Iterator<Item = Option<Pos>> + 'a {
+        let mut cursor = self.cursor();
+        let mut prev = 0;
+        offsets.into_iter().map(move |o| {
+            debug_assert!(prev <= o, "offsets are not sorted");
+            prev = o;
+            cursor.pos_of_forward(o)
+        })
+    }
+
+    /// Locs of byte ranges sorted by start, ends are looked up with a [`LineIndexCursor`]
+    /// so nested ranges stay fast  
+    /// `None` for a range with an end not in the text
+    /// # Panics
+    /// In debug builds if the starts are not sorted
+    pub fn locs_of_sorted_ranges(&self, ranges: &[Range<usize>]) -> Vec<Option<Loc>> {
+        debug_assert!(
+            ranges.windows(2).all(|w| w[0].start <= w[1].start),
+            "ranges are not sorted"
+        );
+        let mut starts = self.cursor();
+        let mut ends = self.cursor();
+        ranges
+            .iter()
+            .map(|r| {
+                Some(Loc::new(
+                    starts.pos_of_forward(r.start)?,
+                    ends.pos_of(r.end)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Loc of the first match of the needle in the indexed source, as [`str::find`]
+    pub fn find(&self, src: &str, needle: &str) -> Option<Loc> {
+        let start = src.find(needle)?;
+        Some(Loc::new(
+            self.pos_of(start)?,
+            self.pos_of(start + needle.len())?,
+        ))
+    }
+
+    /// Locs of the non-overlapping matches of the needle in the indexed source,
+    /// as [`str::match_indices`], in one pass over the lines
+    pub fn find_all<'a>(&'a self, src: &'a str, needle: &'a str) -> impl Iterator<Item = Loc> + 'a {
+        let mut cursor = self.cursor();
+        src.match_indices(needle).filter_map(move |(start, m)| {
+            let from = cursor.pos_of_forward(start)?;
+            Some(Loc::new(from, cursor.pos_of_forward(start + m.len())?))
+        })
+    }
+
+    /// Char offset of the pos from the text start  
+    /// `None` if the line does not exist or the column is past the line end
+    pub fn char_offset_of(&self, pos: Pos) -> Option<usize> {
+        let offset = self.offset_of(pos)?;
+        let extra: usize = self
+            .wide_in(0..offset)
+            .iter()
+            .map(|&(_, len)| len as usize - 1)
+            .sum();
+        Some(offset - extra)
+    }
+}
+
+/// [`LineIndex::pos_of`] remembering the line of the last lookup  
+/// A lookup on the same or the next line skips the binary search,
+/// so a sorted stream of offsets costs O(1) amortized each, any other order is still correct
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let index = LineIndex::new("let a\n= 1;\n");
+/// let mut cursor = index.cursor();
+/// let tokens: Vec<_> = [0, 4, 6, 8].iter().map(|&o| cursor.pos_of(o)).collect();
+/// assert_eq!(tokens, [Some(pos(0, 0)), Some(pos(0, 4)), Some(pos(1, 0)), Some(pos(1, 2))]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndexCursor<'a> {
+    index: &'a LineIndex,
+    /// line of the last lookup
+    line: usize,
+    /// first non-ascii char at or after the line start
+    wide: usize,
+}
+
+impl LineIndexCursor<'_> {
+    /// Same as [`LineIndex::pos_of`]
+    pub fn pos_of(&mut self, offset: usize) -> Option<Pos> {
+        if offset > self.index.len {
+            return None;
+        }
+        if !self.on(self.line, offset) {
+            if self.line + 1 < self.index.lines.len() && self.on(self.line + 1, offset) {
+                self.step();
+            } else {
+                self.jump(offset);
+            }
+        }
+        self.column_of(offset)
+    }
+
+    /// [`LineIndexCursor::pos_of`] walking the lines forward to the offset however far,
+    /// the merged pass of [`LineIndex::positions_of_sorted`]
+    fn pos_of_forward(&mut self, offset: usize) -> Option<Pos> {
+        if offset > self.index.len {
+            return None;
+        }
+        if offset < self.index.lines[self.line] {
+            self.jump(offset);
+        }
+        while !self.on(self.line, offset) {
+            self.step();
+        }
+        self.column_of(offset)
+    }
+
+    /// Is the offset on the line
+    fn on(&self, line: usize, offset: usize) -> bool {
+        let lines = &self.index.lines;
+        lines[line] <= offset && lines.get(line + 1).map_or(true, |&next| offset < next)
+    }
+
+    /// Move to the next line
+    fn step(&mut self) {
+        let index = self.index;
+        self.line += 1;
+        let start = index.lines[self.line];
+        while index.wide.get(self.wide).is_some_and(|&(at, _)| at < start) {
+            self.wide += 1;
+        }
+    }
+
+    /// Move to the line of the offset with a binary search
+    fn jump(&mut self, offset: usize) {
+        let index = self.index;
+        self.line = index.line_of(offset);
+        let start = index.lines[self.line];
+        self.wide = index.wide.partition_point(|&(at, _)| at < start);
+    }
+
+    /// Pos of the offset on the current line
+    fn column_of(&self, offset: usize) -> Option<Pos> {
+        let index = self.index;
+        let mut column = offset - index.lines[self.line];
+        for &(at, len) in index.wide[self.wide..]
+            .iter()
+            .take_while(|&&(at, _)| at < offset)
+        {
+            if offset < at + len as usize {
+                return None;
+            }
+            column -= len as usize - 1;
+        }
+        Some(Pos::new(self.line, column))
+    }
+}
+
+impl Pos {
+    /// Pull a column past the line end back to the line end  
+    /// A line past the text snaps to [`LineIndex::end_pos`]
+    pub fn clamp_column(self, index: &LineIndex) -> Pos {
+        match index.line_len(self.line) {
+            Some(len) => self.with_column(self.column.min(len)),
+            None => index.end_pos(),
+        }
+    }
+}
+
+impl Loc {
+    /// Byte range of the loc in the index
+    pub fn byte_range(&self, index: &LineIndex) -> Option<Range<usize>> {
+        let from = index.offset_of(self.from)?;
+        let to = index.offset_of(self.to)?;
+        if from <= to {
+            Some(from..to)
+        } else {
+            None
+        }
+    }
+
+    /// Is a zero width loc at the end of the text
+    pub fn is_eof_in(&self, index: &LineIndex) -> bool {
+        self.from == self.to && self.from == index.end_pos()
+    }
+
+    /// Line and caret of an EOF loc, caret just after the last char,
+    /// on the last non-empty line if the text ends with `\n`  
+    /// `None` if not [`Loc::is_eof_in`]
+    pub fn eof_snippet_in(&self, text: &str, index: &LineIndex) -> Option<String> {
+        if !self.is_eof_in(index) {
+            return None;
+        }
+        let line = text
+            .trim_end_matches('\n')
+            .rsplit('\n')
+            .next()
+            .unwrap_or("");
+        let mut out = String::from(line);
+        out.push('\n');
+        out.extend(core::iter::repeat(' ').take(line.chars().count()));
+        out.push('^');
+        Some(out)
+    }
+
+    /// Every valid pos from `from` to `to` inclusive, wrapping to the next line after each line end  
+    /// Yields one pos per char of the span plus one per line end, can be large for big spans  
+    /// Empty if `from` is not in the index or after `to`, a `to` past its line end stops at the line end
+    pub fn iter_positions<'a>(&self, index: &'a LineIndex) -> impl Iterator<Item = Pos> + 'a {
+        let (from, to) = (self.from, self.to.clamp_column(index));
+        let first = if index.offset_of(from).is_some() && from <= to {
+            Some(from)
+        } else {
+            None
+        };
+        core::iter::successors(first, move |&p| {
+            if p >= to {
+                return None;
+            }
+            let end = index.line_range(p.line).and_then(|r| index.pos_of(r.end))?;
+            if p.column < end.column {
+                Some(p.with_column(p.column + 1))
+            } else if p.line + 1 < index.line_count() {
+                Some(Pos::new(p.line + 1, 0))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Byte len of the loc in the index
+    pub fn byte_len(&self, index: &LineIndex) -> Option<usize> {
+        self.byte_range(index).map(|r| r.len())
+    }
+
+    /// Byte len of the intersection  
+    /// `0` if disjoint or not in the index
+    pub fn overlap_len(&self, other: &Loc, index: &LineIndex) -> usize {
+        self.intersection(other)
+            .and_then(|l| l.byte_len(index))
+            .unwrap_or(0)
+    }
+
+    /// Jaccard similarity, overlap len / union len, in `0.0..=1.0`  
+    /// If the union len is zero (both are empty), `1.0` if equal else `0.0`  
+    /// `0.0` if either is not in the index
+    pub fn similarity(&self, other: &Loc, index: &LineIndex) -> f64 {
+        let (a, b) = match (self.byte_len(index), other.byte_len(index)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return 0.0,
+        };
+        let overlap = self.overlap_len(other, index);
+        let union = a + b - overlap;
+        if union == 0 {
+            if self == other {
+                1.0
+            } else {
+                0.0
+            }
+        } else {
+            overlap as f64 / union as f64
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Loc, Pos};
+#[cfg(feature = "alloc")]
+use alloc::format;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::fmt::Display;
+
+impl Loc {
+    /// GitHub fragment, one based  
+    /// By line `#L12-L14`, or `#L12` for a single line  
+    /// With `columns`, `#L12C5-L14C9`, or `#L12C5` for an empty loc,
+    /// the end column is inclusive  
+    /// An end at column 0 is the end of the previous line and gives a by line fragment,
+    /// a [`Pos::DUMMY`] end is the start, and a [`Pos::DUMMY`] start gives an empty fragment
+    #[inline]
+    pub const fn github_fragment(&self, columns: bool) -> GithubFragment {
+        GithubFragment {
+            loc: *self,
+            columns,
+        }
+    }
+
+    /// GitHub line fragment, `#L12-L14` or `#L12` for a single line  
+    /// Loc is zero based, the fragment is one based
+    #[cfg(feature = "alloc")]
+    pub fn to_github_fragment(&self) -> String {
+        self.github_fragment(false).to_string()
+    }
+
+    /// GitHub column precise fragment, `#L12C5-L14C9` or `#L12C5` for an empty loc  
+    /// Loc is zero based with an exclusive end, the fragment is one based with an inclusive end
+    #[cfg(feature = "alloc")]
+    pub fn to_github_fragment_with_columns(&self) -> String {
+        self.github_fragment(true).to_string()
+    }
+
+    /// GitLab line fragment, `#L12-14` or `#L12` for a single line  
+    /// Loc is zero based, the fragment is one based, ends are handled as in [`Loc::github_fragment`]
+    #[cfg(feature = "alloc")]
+    pub fn to_gitlab_fragment(&self) -> String {
+        match fragment_lines(self) {
+            None => String::new(),
+            Some((from, to)) if from == to => format!("#L{}", from),
+            Some((from, to)) => format!("#L{}-{}", from, to),
+        }
+    }
+}
+
+impl Pos {
+    /// GitHub fragment of the pos, `#L12C5`, one based
+    #[inline]
+    pub const fn github_fragment(&self) -> GithubFragment {
+        Loc::new_same_pos(*self).github_fragment(true)
+    }
+}
+
+/// Display adapter, see [`Loc::github_fragment`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GithubFragment {
+    loc: Loc,
+    columns: bool,
+}
+
+/// One based first and last line of the loc, `None` for a [`Pos::DUMMY`] start  
+/// A [`Pos::DUMMY`] end is the start, an end at column 0 of a later line is the end of the previous line
+fn fragment_lines(loc: &Loc) -> Option<(usize, usize)> {
+    let Loc { from, to } = *loc;
+    if from.is_dummy() {
+        None
+    } else if to.is_dummy() {
+        Some((from.line.saturating_add(1), from.line.saturating_add(1)))
+    } else if to.column == 0 && to.line > from.line {
+        Some((from.line.saturating_add(1), to.line))
+    } else {
+        Some((from.line.saturating_add(1), to.line.saturating_add(1)))
+    }
+}
+
+impl Display for GithubFragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Loc { from, to } = self.loc;
+        let (first, last) = match fragment_lines(&self.loc) {
+            Some(lines) => lines,
+            None => return Ok(()),
+        };
+        let to = if to.is_dummy() { from } else { to };
+        if !self.columns || (to.column == 0 && to.line > from.line) {
+            if first == last {
+                write!(f, "#L{}", first)
+            } else {
+                write!(f, "#L{}-L{}", first, last)
+            }
+        } else if from == to {
+            write!(
+                f,
+                "#L{}C{}",
+                from.line.saturating_add(1),
+                from.column.saturating_add(1)
+            )
+        } else {
+            write!(
+                f,
+                "#L{}C{}-L{}C{}",
+                from.line.saturating_add(1),
+                from.column.saturating_add(1),
+                to.line.saturating_add(1),
+                to.column
+            )
+        }
+    }
+}
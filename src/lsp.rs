@@ -0,0 +1,161 @@
+//! LSP `Diagnostic` of a [`Diagnostic`], as plain structs serializing to the LSP JSON shape  
+//! Positions are zero based like [`Pos`], `character` is in the negotiated [`PositionEncoding`]
+
+use crate::{Diagnostic, LineIndex, Loc, Pos, PositionEncoding, Severity};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use serde::{Deserialize, Serialize};
+
+/// LSP `Position`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct LspPosition {
+    /// zero based line
+    pub line: u32,
+    /// zero based column in the position encoding
+    pub character: u32,
+}
+
+/// LSP `Range`, the end is exclusive
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+pub struct LspRange {
+    /// start
+    pub start: LspPosition,
+    /// exclusive end
+    pub end: LspPosition,
+}
+
+/// LSP `Location`
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct LspLocation {
+    /// document uri
+    pub uri: String,
+    /// range in the document
+    pub range: LspRange,
+}
+
+/// LSP `DiagnosticRelatedInformation`
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct LspRelatedInformation {
+    /// location
+    pub location: LspLocation,
+    /// message
+    pub message: String,
+}
+
+/// LSP `Diagnostic`, without the uri of the document it is published for
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LspDiagnostic {
+    /// range in the document
+    pub range: LspRange,
+    /// `DiagnosticSeverity`, see [`Severity::to_lsp`]
+    pub severity: u8,
+    /// optional code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// message
+    pub message: String,
+    /// the labels
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub related_information: Vec<LspRelatedInformation>,
+}
+
+impl Severity {
+    /// LSP `DiagnosticSeverity`, `1` error, `2` warning, `3` information for a note, `4` hint for a help
+    #[inline]
+    pub const fn to_lsp(self) -> u8 {
+        match self {
+            Self::Error => 1,
+            Self::Warning => 2,
+            Self::Note => 3,
+            Self::Help => 4,
+        }
+    }
+}
+
+impl Pos {
+    /// LSP position of the pos in the index  
+    /// `None` if the pos is not in the index or out of `u32`
+    pub fn to_lsp_position(
+        &self,
+        index: &LineIndex,
+        encoding: PositionEncoding,
+    ) -> Option<LspPosition> {
+        let character = index.encode_column(*self, encoding)?;
+        Some(LspPosition {
+            line: u32::try_from(self.line).ok()?,
+            character: u32::try_from(character).ok()?,
+        })
+    }
+
+    /// Pos of the LSP position in the index, see [`LineIndex::decode_column`]
+    #[inline]
+    pub fn from_lsp_position(
+        position: LspPosition,
+        index: &LineIndex,
+        encoding: PositionEncoding,
+    ) -> Option<Pos> {
+        index.decode_column(
+            position.line as usize,
+            position.character as usize,
+            encoding,
+        )
+    }
+}
+
+impl Loc {
+    /// LSP range of the loc in the index, see [`Pos::to_lsp_position`]
+    pub fn to_lsp_range(&self, index: &LineIndex, encoding: PositionEncoding) -> Option<LspRange> {
+        Some(LspRange {
+            start: self.from.to_lsp_position(index, encoding)?,
+            end: self.to.to_lsp_position(index, encoding)?,
+        })
+    }
+
+    /// Loc of the LSP range in the index, see [`Pos::from_lsp_position`]
+    pub fn from_lsp_range(
+        range: LspRange,
+        index: &LineIndex,
+        encoding: PositionEncoding,
+    ) -> Option<Loc> {
+        Some(Loc::new(
+            Pos::from_lsp_position(range.start, index, encoding)?,
+            Pos::from_lsp_position(range.end, index, encoding)?,
+        ))
+    }
+}
+
+impl Diagnostic {
+    /// LSP diagnostic, the loc is the range and the labels are the related information  
+    /// `resolve` gives the uri and line index of a file  
+    /// `None` if a file does not resolve or a loc is not in its index
+    pub fn to_lsp<'i>(
+        &self,
+        encoding: PositionEncoding,
+        mut resolve: impl FnMut(&str) -> Option<(String, &'i LineIndex)>,
+    ) -> Option<LspDiagnostic> {
+        let (_, index) = resolve(&self.file)?;
+        let related_information = self
+            .labels
+            .iter()
+            .map(|label| {
+                let (uri, index) = resolve(&label.file)?;
+                Some(LspRelatedInformation {
+                    location: LspLocation {
+                        uri,
+                        range: label.loc.to_lsp_range(index, encoding)?,
+                    },
+                    message: label.message.clone(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(LspDiagnostic {
+            range: self.loc.to_lsp_range(index, encoding)?,
+            severity: self.severity.to_lsp(),
+            code: self.code.clone(),
+            message: self.message.clone(),
+            related_information,
+        })
+    }
+}
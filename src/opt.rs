@@ -0,0 +1,158 @@
+//! [`OptPos`] and [`OptLoc`], `Option` shaped without the extra word
+//!
+//! The dummies are the `None` sentinel, so they are as large as [`Pos`] and [`Loc`]
+//! where `Option<Pos>` and `Option<Loc>` need a tag.
+//! A `Some` of a dummy is `None`.  
+//! Ordering, `Debug` and serde match `Option`, `None` sorts first and serializes as `null`.
+//!
+//! # Examples
+//! ```
+//! # use srcpos::*;
+//! let mut span = OptLoc::NONE;
+//! assert!(span.is_none());
+//! span = locof!(1, 2, 3, 4).into();
+//! assert_eq!(span.get(), Some(locof!(1, 2, 3, 4)));
+//! assert!(OptLoc::NONE < span);
+//! ```
+
+use crate::{Loc, Pos};
+use core::cmp::Ordering;
+use core::fmt;
+use core::mem::size_of;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const _: () = assert!(size_of::<OptPos>() == size_of::<Pos>());
+const _: () = assert!(size_of::<OptLoc>() == size_of::<Loc>());
+
+macro_rules! opt {
+    ($(#[$doc:meta])* $opt:ident, $inner:ident) => {
+        $(#[$doc])*
+        #[derive(PartialEq, Eq, Clone, Copy, Hash)]
+        #[repr(transparent)]
+        pub struct $opt($inner);
+
+        impl $opt {
+            /// The `None`
+            pub const NONE: Self = Self($inner::DUMMY);
+
+            /// New from an option, `Some` of a dummy is `None`
+            #[inline]
+            pub const fn new(value: Option<$inner>) -> Self {
+                match value {
+                    Some(value) => Self(value),
+                    None => Self::NONE,
+                }
+            }
+            /// New `Some`, a dummy is `None`
+            #[inline]
+            pub const fn some(value: $inner) -> Self {
+                Self(value)
+            }
+            /// Is not `None`
+            #[inline]
+            pub const fn is_some(&self) -> bool {
+                !self.0.is_dummy()
+            }
+            /// Is `None`
+            #[inline]
+            pub const fn is_none(&self) -> bool {
+                self.0.is_dummy()
+            }
+            /// As an `Option`
+            #[inline]
+            pub const fn get(&self) -> Option<$inner> {
+                if self.is_some() {
+                    Some(self.0)
+                } else {
+                    None
+                }
+            }
+            /// The value, the dummy if `None`
+            #[inline]
+            pub const fn unwrap_or_dummy(self) -> $inner {
+                self.0
+            }
+            /// Take the value out, leaving `None`
+            #[inline]
+            pub fn take(&mut self) -> Option<$inner> {
+                core::mem::replace(self, Self::NONE).get()
+            }
+        }
+
+        impl Default for $opt {
+            #[inline(always)]
+            fn default() -> Self {
+                Self::NONE
+            }
+        }
+
+        impl Ord for $opt {
+            #[inline]
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.get().cmp(&other.get())
+            }
+        }
+
+        impl PartialOrd for $opt {
+            #[inline]
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl fmt::Debug for $opt {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.get().fmt(f)
+            }
+        }
+
+        impl From<$inner> for $opt {
+            #[inline]
+            fn from(value: $inner) -> Self {
+                Self::some(value)
+            }
+        }
+
+        impl From<Option<$inner>> for $opt {
+            #[inline]
+            fn from(value: Option<$inner>) -> Self {
+                Self::new(value)
+            }
+        }
+
+        impl From<$opt> for Option<$inner> {
+            #[inline]
+            fn from(value: $opt) -> Self {
+                value.get()
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl Serialize for $opt {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                self.get().serialize(serializer)
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> Deserialize<'de> for $opt {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                Option::<$inner>::deserialize(deserializer).map(Self::new)
+            }
+        }
+    };
+}
+
+opt!(
+    /// `Option<Pos>` in the size of a [`Pos`], see the [module docs](self)
+    OptPos,
+    Pos
+);
+opt!(
+    /// `Option<Loc>` in the size of a [`Loc`], see the [module docs](self)
+    OptLoc,
+    Loc
+);
@@ -0,0 +1,199 @@
+use crate::{Loc, Pos};
+use core::fmt;
+use core::fmt::Display;
+use core::str::FromStr;
+
+/// Error of parsing `Pos` or `Loc`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct ParseError {
+    msg: &'static str,
+}
+impl ParseError {
+    /// What went wrong
+    #[inline]
+    pub const fn message(&self) -> &'static str {
+        self.msg
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+const fn err<T>(msg: &'static str) -> Result<T, ParseError> {
+    Err(ParseError { msg })
+}
+
+/// Decimal number at `i`, and the index after it
+const fn number(b: &[u8], mut i: usize) -> Result<(usize, usize), ParseError> {
+    let start = i;
+    let mut n: usize = 0;
+    while i < b.len() && b[i].is_ascii_digit() {
+        n = match n.checked_mul(10) {
+            Some(n) => match n.checked_add((b[i] - b'0') as usize) {
+                Some(n) => n,
+                None => return err("number too large"),
+            },
+            None => return err("number too large"),
+        };
+        i += 1;
+    }
+    if i == start {
+        return err("expected a number");
+    }
+    Ok((n, i))
+}
+
+/// `line:column` at `i`, and the index after it
+const fn pos_at(b: &[u8], i: usize) -> Result<(Pos, usize), ParseError> {
+    let (line, i) = match number(b, i) {
+        Ok(v) => v,
+        Err(e) => return Err(e),
+    };
+    if i >= b.len() || b[i] != b':' {
+        return err("expected `:`");
+    }
+    let (column, i) = match number(b, i + 1) {
+        Ok(v) => v,
+        Err(e) => return Err(e),
+    };
+    Ok((Pos::new(line, column), i))
+}
+
+impl Pos {
+    /// Parse `line:column`
+    pub const fn parse(s: &str) -> Result<Pos, ParseError> {
+        let b = s.as_bytes();
+        match pos_at(b, 0) {
+            Ok((p, i)) if i == b.len() => Ok(p),
+            Ok(_) => err("unexpected trailing characters"),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl Loc {
+    /// Parse `line:column-line:column`, `line:column-column` on one line,
+    /// or `line:column` for an empty loc
+    pub const fn parse(s: &str) -> Result<Loc, ParseError> {
+        let b = s.as_bytes();
+        let (from, i) = match pos_at(b, 0) {
+            Ok(v) => v,
+            Err(e) => return Err(e),
+        };
+        if i == b.len() {
+            return Ok(Loc::new_same_pos(from));
+        }
+        if b[i] != b'-' {
+            return err("expected `-`");
+        }
+        let (n, i) = match number(b, i + 1) {
+            Ok(v) => v,
+            Err(e) => return Err(e),
+        };
+        if i == b.len() {
+            return Ok(Loc::new(from, Pos::new(from.line, n)));
+        }
+        if b[i] != b':' {
+            return err("unexpected trailing characters");
+        }
+        let (column, i) = match number(b, i + 1) {
+            Ok(v) => v,
+            Err(e) => return Err(e),
+        };
+        if i != b.len() {
+            return err("unexpected trailing characters");
+        }
+        Ok(Loc::new(from, Pos::new(n, column)))
+    }
+}
+
+/// See [`Pos::parse`]
+impl FromStr for Pos {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// See [`Loc::parse`]
+impl FromStr for Loc {
+    type Err = ParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Parse a Pos literal at compile time, see [`Pos::parse`]
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// const P: Pos = pos_str!("3:7");
+/// assert_eq!(P, pos(3, 7));
+/// ```
+#[macro_export]
+macro_rules! pos_str {
+    ($s:literal) => {{
+        const POS: $crate::Pos = match $crate::Pos::parse($s) {
+            Ok(p) => p,
+            Err(e) => panic!("{}", e.message()),
+        };
+        POS
+    }};
+}
+
+/// Parse a Loc literal at compile time, see [`Loc::parse`]
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// const L: Loc = loc_str!("3:7-3:12");
+/// assert_eq!(L, locof!(3, 7, 3, 12));
+/// assert_eq!(loc_str!("3:7-12"), L);
+/// ```
+#[macro_export]
+macro_rules! loc_str {
+    ($s:literal) => {{
+        const LOC: $crate::Loc = match $crate::Loc::parse($s) {
+            Ok(l) => l,
+            Err(e) => panic!("{}", e.message()),
+        };
+        LOC
+    }};
+}
+
+/// Alias of [`pos_str!`], a malformed literal fails to compile
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// const P: Pos = pos_lit!("12:5");
+/// assert_eq!(P, pos(12, 5));
+/// ```
+#[macro_export]
+macro_rules! pos_lit {
+    ($s:literal) => {
+        $crate::pos_str!($s)
+    };
+}
+
+/// Alias of [`loc_str!`], a malformed literal fails to compile
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// const L: Loc = loc_lit!("12:5-14:9");
+/// assert_eq!(L, locof!(12, 5, 14, 9));
+/// ```
+#[macro_export]
+macro_rules! loc_lit {
+    ($s:literal) => {
+        $crate::loc_str!($s)
+    };
+}
@@ -0,0 +1,13 @@
+//! Commonly used items
+//!
+//! ```
+//! use srcpos::prelude::*;
+//! let a = loc(pos(1, 2), pos(3, 4));
+//! let b: Loc = locof!(1, 2, 3, 4);
+//! assert_eq!(a, b);
+//! ```
+
+pub use crate::{loc, locof, pos, posof, spanned, Loc, Pos, Spanned};
+
+#[cfg(feature = "alloc")]
+pub use crate::LineIndex;
@@ -0,0 +1,57 @@
+use crate::{Loc, Pos, Spanned};
+use quickcheck::{Arbitrary, Gen};
+
+impl Arbitrary for Pos {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self::new(usize::arbitrary(g), usize::arbitrary(g))
+    }
+
+    /// Shrinks the line first, then the column
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let Pos { line, column } = *self;
+        Box::new(
+            line.shrink()
+                .map(move |line| Self::new(line, column))
+                .chain(column.shrink().map(move |column| Self::new(line, column))),
+        )
+    }
+}
+
+impl Arbitrary for Loc {
+    /// Always `from <= to`
+    fn arbitrary(g: &mut Gen) -> Self {
+        let a = Pos::arbitrary(g);
+        let b = Pos::arbitrary(g);
+        Self::new(a.min(b), a.max(b))
+    }
+
+    /// Shrinks `from` first, then `to`, keeping `from <= to`
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let Loc { from, to } = *self;
+        Box::new(
+            from.shrink().map(move |from| Self::new(from, to)).chain(
+                to.shrink()
+                    .filter(move |to| from <= *to)
+                    .map(move |to| Self::new(from, to)),
+            ),
+        )
+    }
+}
+
+impl<T: Arbitrary> Arbitrary for Spanned<T> {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Self::new(T::arbitrary(g), Loc::arbitrary(g))
+    }
+
+    /// Shrinks the value first, then the loc
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let loc = self.loc;
+        let value = self.value.clone();
+        Box::new(
+            self.value
+                .shrink()
+                .map(move |value| Self::new(value, loc))
+                .chain(loc.shrink().map(move |loc| Self::new(value.clone(), loc))),
+        )
+    }
+}
@@ -0,0 +1,35 @@
+use crate::{Loc, Pos};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Message on one line, newlines become spaces
+fn flatten(message: &str) -> String {
+    message.replace("\r\n", " ").replace(['\r', '\n'], " ")
+}
+
+/// Vim quickfix / `grep -n` line, `file:line:col: message` (errorformat `%f:%l:%c: %m`)  
+/// Pos is zero based, the output is one based
+pub fn quickfix_line(file: &str, pos: Pos, message: &str) -> String {
+    format!("{}: {}", pos.display_gnu(file), flatten(message))
+}
+
+/// [`quickfix_line`] at the start of the loc
+#[inline]
+pub fn quickfix_line_loc(file: &str, loc: Loc, message: &str) -> String {
+    quickfix_line(file, loc.from, message)
+}
+
+/// Write [`quickfix_line`]s sorted by file then pos, one per line
+pub fn write_quickfix<'a, W: fmt::Write>(
+    mut w: W,
+    items: impl IntoIterator<Item = (&'a str, Pos, &'a str)>,
+) -> fmt::Result {
+    let mut items: Vec<_> = items.into_iter().collect();
+    items.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+    for (file, pos, message) in items {
+        writeln!(w, "{}", quickfix_line(file, pos, message))?;
+    }
+    Ok(())
+}
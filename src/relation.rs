@@ -0,0 +1,67 @@
+use crate::Loc;
+
+/// How a loc relates to another, see [`Loc::relation_to`]  
+/// Locs are half open, `from..to`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SpanRelation {
+    /// Ends before the other starts, with a gap
+    Before,
+    /// Starts after the other ends, with a gap
+    After,
+    /// Shares some but not all of the other, and the other some but not all of it
+    Overlaps,
+    /// The other is inside it
+    Contains,
+    /// It is inside the other
+    ContainedBy,
+    /// Same from and to
+    Equal,
+    /// One ends exactly where the other starts
+    Adjacent,
+}
+
+impl SpanRelation {
+    /// Swap the sides, `a.relation_to(&b).flip() == b.relation_to(&a)`
+    #[inline]
+    pub const fn flip(self) -> Self {
+        match self {
+            Self::Before => Self::After,
+            Self::After => Self::Before,
+            Self::Contains => Self::ContainedBy,
+            Self::ContainedBy => Self::Contains,
+            other => other,
+        }
+    }
+}
+
+impl Loc {
+    /// Relation to the other loc, when several apply the first of
+    /// `Equal`, `Contains`, `ContainedBy`, `Adjacent`, `Overlaps`, `Before` and `After` wins  
+    /// So an empty loc on the edge of another is contained by it, not adjacent
+    /// # Examples
+    /// ```
+    /// # use srcpos::*;
+    /// let a = locof!(0, 0, 0, 4);
+    /// assert_eq!(a.relation_to(&locof!(0, 1, 0, 2)), SpanRelation::Contains);
+    /// assert_eq!(a.relation_to(&locof!(0, 4, 0, 6)), SpanRelation::Adjacent);
+    /// assert_eq!(a.relation_to(&locof!(0, 2, 0, 6)), SpanRelation::Overlaps);
+    /// assert_eq!(a.relation_to(&locof!(1, 0, 1, 1)), SpanRelation::Before);
+    /// ```
+    pub fn relation_to(&self, other: &Loc) -> SpanRelation {
+        if self == other {
+            SpanRelation::Equal
+        } else if self.contains_loc(other) {
+            SpanRelation::Contains
+        } else if other.contains_loc(self) {
+            SpanRelation::ContainedBy
+        } else if self.is_adjacent_to(other) {
+            SpanRelation::Adjacent
+        } else if self.from < other.to && other.from < self.to {
+            SpanRelation::Overlaps
+        } else if self.to <= other.from {
+            SpanRelation::Before
+        } else {
+            SpanRelation::After
+        }
+    }
+}
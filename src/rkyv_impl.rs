@@ -0,0 +1,32 @@
+use crate::{ArchivedLoc, ArchivedPos, Loc, Pos};
+
+impl ArchivedPos {
+    /// Read out the `Pos`
+    #[inline]
+    pub fn to_pos(&self) -> Pos {
+        Pos::new(self.line as usize, self.column as usize)
+    }
+}
+
+impl ArchivedLoc {
+    /// Read out the `Loc`
+    #[inline]
+    pub fn to_loc(&self) -> Loc {
+        Loc::new(self.from.to_pos(), self.to.to_pos())
+    }
+    /// Same as [`Loc::contains`]
+    #[inline]
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.to_loc().contains(pos)
+    }
+    /// Same as [`Loc::contains_loc`]
+    #[inline]
+    pub fn contains_loc(&self, other: &Loc) -> bool {
+        self.to_loc().contains_loc(other)
+    }
+    /// Same as [`Loc::merge`]
+    #[inline]
+    pub fn merge(&self, other: &Loc) -> Loc {
+        self.to_loc().merge(other)
+    }
+}
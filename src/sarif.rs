@@ -0,0 +1,116 @@
+//! SARIF `region` and minimal `result` objects  
+//! SARIF lines and columns are one based and `endColumn` is exclusive, like a one based [`Loc`]
+
+use crate::{Loc, Pos};
+use serde::{Deserialize, Serialize};
+
+/// SARIF `region` object
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifRegion {
+    /// one based line
+    pub start_line: usize,
+    /// one based column
+    pub start_column: usize,
+    /// one based line
+    pub end_line: usize,
+    /// one based exclusive column
+    pub end_column: usize,
+}
+
+/// SARIF `result` object with a single physical location
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult<'a> {
+    /// rule id
+    pub rule_id: &'a str,
+    /// message
+    pub message: SarifMessage<'a>,
+    /// locations
+    pub locations: [SarifLocation<'a>; 1],
+}
+
+/// SARIF `message` object
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub struct SarifMessage<'a> {
+    /// text
+    pub text: &'a str,
+}
+
+/// SARIF `location` object
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifLocation<'a> {
+    /// physical location
+    pub physical_location: SarifPhysicalLocation<'a>,
+}
+
+/// SARIF `physicalLocation` object
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifPhysicalLocation<'a> {
+    /// artifact location
+    pub artifact_location: SarifArtifactLocation<'a>,
+    /// region, left out for the whole file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<SarifRegion>,
+}
+
+/// SARIF `artifactLocation` object
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize)]
+pub struct SarifArtifactLocation<'a> {
+    /// uri
+    pub uri: &'a str,
+}
+
+impl Loc {
+    /// SARIF region of the zero based loc, `None` if either end is [`Pos::DUMMY`]
+    #[inline]
+    pub const fn to_sarif_region(&self) -> Option<SarifRegion> {
+        if self.from.is_dummy() || self.to.is_dummy() {
+            return None;
+        }
+        Some(SarifRegion {
+            start_line: self.from.line.saturating_add(1),
+            start_column: self.from.column.saturating_add(1),
+            end_line: self.to.line.saturating_add(1),
+            end_column: self.to.column.saturating_add(1),
+        })
+    }
+
+    /// Zero based loc of the SARIF region, `None` if any line or column is 0
+    #[inline]
+    pub const fn from_sarif_region(region: &SarifRegion) -> Option<Loc> {
+        if region.start_line == 0
+            || region.start_column == 0
+            || region.end_line == 0
+            || region.end_column == 0
+        {
+            return None;
+        }
+        Some(Loc::new(
+            Pos::new(region.start_line - 1, region.start_column - 1),
+            Pos::new(region.end_line - 1, region.end_column - 1),
+        ))
+    }
+
+    /// Minimal SARIF result at the loc in file, without a region if [`Loc::to_sarif_region`] is `None`
+    #[inline]
+    pub const fn to_sarif_result<'a>(
+        &self,
+        file: &'a str,
+        rule_id: &'a str,
+        message: &'a str,
+    ) -> SarifResult<'a> {
+        SarifResult {
+            rule_id,
+            message: SarifMessage { text: message },
+            locations: [SarifLocation {
+                physical_location: SarifPhysicalLocation {
+                    artifact_location: SarifArtifactLocation { uri: file },
+                    region: self.to_sarif_region(),
+                },
+            }],
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{LineIndex, Loc, PositionEncoding};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+
+/// Error of [`encode_semantic_tokens`]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum SemanticTokenError {
+    /// The token spans lines
+    MultiLine(Loc),
+    /// The token is not in the index or out of `u32`
+    OutOfRange(Loc),
+}
+
+impl fmt::Display for SemanticTokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MultiLine(l) => write!(f, "semantic token {} spans lines", l.bare()),
+            Self::OutOfRange(l) => write!(f, "semantic token {} is out of the text", l.bare()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SemanticTokenError {}
+
+/// LSP semantic tokens data of `(loc, token type, modifiers)`,
+/// `[deltaLine, deltaStartChar, length, tokenType, tokenModifiers]` per token  
+/// The tokens are normalized and sorted by loc first, each must be on a single line
+pub fn encode_semantic_tokens(
+    tokens: &[(Loc, u32, u32)],
+    encoding: PositionEncoding,
+    index: &LineIndex,
+) -> Result<Vec<u32>, SemanticTokenError> {
+    let mut tokens: Vec<_> = tokens
+        .iter()
+        .map(|&(loc, kind, modifiers)| (loc.normalize(), kind, modifiers))
+        .collect();
+    tokens.sort_by_key(|t| t.0);
+    let mut data = Vec::with_capacity(tokens.len() * 5);
+    let (mut prev_line, mut prev_start) = (0, 0);
+    for (loc, kind, modifiers) in tokens {
+        if loc.from.line != loc.to.line {
+            return Err(SemanticTokenError::MultiLine(loc));
+        }
+        let out = || SemanticTokenError::OutOfRange(loc);
+        let start = index.encode_column(loc.from, encoding).ok_or_else(out)?;
+        let end = index.encode_column(loc.to, encoding).ok_or_else(out)?;
+        let line = loc.from.line;
+        let delta_start = if line == prev_line {
+            start - prev_start
+        } else {
+            start
+        };
+        for v in [line - prev_line, delta_start, end - start] {
+            data.push(u32::try_from(v).map_err(|_| out())?);
+        }
+        data.push(kind);
+        data.push(modifiers);
+        prev_line = line;
+        prev_start = start;
+    }
+    Ok(data)
+}
+
+/// Inverse of [`encode_semantic_tokens`]  
+/// `None` if the data is not whole tokens or a token is not in the index
+pub fn decode_semantic_tokens(
+    data: &[u32],
+    encoding: PositionEncoding,
+    index: &LineIndex,
+) -> Option<Vec<(Loc, u32, u32)>> {
+    if data.len() % 5 != 0 {
+        return None;
+    }
+    let mut tokens = Vec::with_capacity(data.len() / 5);
+    let (mut line, mut start) = (0usize, 0usize);
+    for t in data.chunks_exact(5) {
+        let delta_line = t[0] as usize;
+        start = if delta_line == 0 {
+            start + t[1] as usize
+        } else {
+            t[1] as usize
+        };
+        line += delta_line;
+        let from = index.decode_column(line, start, encoding)?;
+        let to = index.decode_column(line, start + t[2] as usize, encoding)?;
+        tokens.push((Loc::new(from, to), t[3], t[4]));
+    }
+    Some(tokens)
+}
@@ -0,0 +1,25 @@
+//! Tolerant serde form of [`Loc`], use with `#[serde(with = "srcpos::serde_any")]`
+//!
+//! Serializes as the default struct form, deserializes from any of
+//! - `[from_line, from_column, to_line, to_column]`
+//! - `[[from_line, from_column], [to_line, to_column]]`
+//! - `{"from": [line, column], "to": [line, column]}`
+//! - `{"from": {"line": 1, "column": 2}, "to": {"line": 3, "column": 4}}`
+//!
+//! Only works with self describing formats.
+
+use crate::serde_compact::LocVisitor;
+use crate::Loc;
+use serde::{Deserializer, Serialize, Serializer};
+
+/// Serialize as the default struct form
+#[inline]
+pub fn serialize<S: Serializer>(loc: &Loc, serializer: S) -> Result<S::Ok, S::Error> {
+    loc.serialize(serializer)
+}
+
+/// Deserialize from any of the accepted forms
+#[inline]
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Loc, D::Error> {
+    deserializer.deserialize_any(LocVisitor { any: true })
+}
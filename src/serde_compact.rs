@@ -0,0 +1,249 @@
+//! Compact serde form, use with `#[serde(with = "srcpos::serde_compact")]`
+//!
+//! `Pos` is `[line, column]` and `Loc` is `[from_line, from_column, to_line, to_column]`,
+//! as fixed length tuples in binary formats.  
+//! Human readable formats also accept the verbose struct form when deserializing,
+//! and `[[from_line, from_column], [to_line, to_column]]` for `Loc`.
+//!
+//! # Examples
+//! ```
+//! # use srcpos::*;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Token {
+//!     #[serde(with = "srcpos::serde_compact")]
+//!     loc: Loc,
+//! }
+//! ```
+
+use crate::{Loc, Pos};
+use core::convert::TryFrom;
+use core::fmt;
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+
+/// Types that have a compact form
+pub trait Compact: Sized {
+    #[doc(hidden)]
+    fn serialize_compact<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+    #[doc(hidden)]
+    fn deserialize_compact<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+/// Serialize in the compact form
+#[inline]
+pub fn serialize<T: Compact, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.serialize_compact(serializer)
+}
+
+/// Deserialize from the compact form, or the other forms if human readable
+#[inline]
+pub fn deserialize<'de, T: Compact, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize_compact(deserializer)
+}
+
+impl Compact for Pos {
+    fn serialize_compact<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut t = serializer.serialize_tuple(2)?;
+        t.serialize_element(&self.line)?;
+        t.serialize_element(&self.column)?;
+        t.end()
+    }
+
+    fn deserialize_compact<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(PosVisitor)
+        } else {
+            deserializer.deserialize_tuple(2, PosVisitor)
+        }
+    }
+}
+
+impl Compact for Loc {
+    fn serialize_compact<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut t = serializer.serialize_tuple(4)?;
+        t.serialize_element(&self.from.line)?;
+        t.serialize_element(&self.from.column)?;
+        t.serialize_element(&self.to.line)?;
+        t.serialize_element(&self.to.column)?;
+        t.end()
+    }
+
+    fn deserialize_compact<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(LocVisitor { any: true })
+        } else {
+            deserializer.deserialize_tuple(4, LocVisitor { any: false })
+        }
+    }
+}
+
+/// Field names of the verbose form
+enum Field {
+    Line,
+    Column,
+    From,
+    To,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+        impl Visitor<'_> for FieldVisitor {
+            type Value = Field;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("field name")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Field, E> {
+                Ok(match v {
+                    "line" | "row" => Field::Line,
+                    "column" | "col" => Field::Column,
+                    "from" | "start" => Field::From,
+                    "to" | "end" => Field::To,
+                    _ => Field::Other,
+                })
+            }
+        }
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+/// Pos in either form
+struct AnyPos(Pos);
+
+impl<'de> Deserialize<'de> for AnyPos {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Pos::deserialize_compact(deserializer).map(AnyPos)
+    }
+}
+
+/// First element of a `Loc` seq
+enum Elem {
+    Num(usize),
+    Pos(Pos),
+}
+
+impl<'de> Deserialize<'de> for Elem {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ElemVisitor;
+        impl<'de> Visitor<'de> for ElemVisitor {
+            type Value = Elem;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a number or a Pos")
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Elem, E> {
+                usize::try_from(v)
+                    .map(Elem::Num)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Elem, A::Error> {
+                PosVisitor.visit_seq(seq).map(Elem::Pos)
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Elem, A::Error> {
+                PosVisitor.visit_map(map).map(Elem::Pos)
+            }
+        }
+        deserializer.deserialize_any(ElemVisitor)
+    }
+}
+
+fn next<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(
+    seq: &mut A,
+    i: usize,
+    expected: &'static str,
+) -> Result<T, A::Error> {
+    seq.next_element()?
+        .ok_or_else(|| de::Error::invalid_length(i, &expected))
+}
+
+struct PosVisitor;
+
+impl<'de> Visitor<'de> for PosVisitor {
+    type Value = Pos;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("[line, column] or a Pos struct")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Pos, A::Error> {
+        let line = next(&mut seq, 0, "2 elements")?;
+        let column = next(&mut seq, 1, "2 elements")?;
+        Ok(Pos::new(line, column))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Pos, A::Error> {
+        let (mut line, mut column) = (None, None);
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Line => line = Some(map.next_value()?),
+                Field::Column => column = Some(map.next_value()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let line = line.ok_or_else(|| de::Error::missing_field("line"))?;
+        let column = column.ok_or_else(|| de::Error::missing_field("column"))?;
+        Ok(Pos::new(line, column))
+    }
+}
+
+/// Accepts the nested pair form too if `any`
+pub(crate) struct LocVisitor {
+    pub(crate) any: bool,
+}
+
+impl<'de> Visitor<'de> for LocVisitor {
+    type Value = Loc;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(
+            "[from_line, from_column, to_line, to_column], \
+             [[from_line, from_column], [to_line, to_column]] or a Loc struct",
+        )
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Loc, A::Error> {
+        let a = if self.any {
+            match next(&mut seq, 0, "2 or 4 elements")? {
+                Elem::Num(a) => a,
+                Elem::Pos(from) => {
+                    let to = next::<AnyPos, _>(&mut seq, 1, "2 elements")?.0;
+                    if seq.next_element::<IgnoredAny>()?.is_some() {
+                        return Err(de::Error::invalid_length(3, &"2 elements"));
+                    }
+                    return Ok(Loc::new(from, to));
+                }
+            }
+        } else {
+            next(&mut seq, 0, "4 elements")?
+        };
+        let b = next(&mut seq, 1, "4 elements")?;
+        let c = next(&mut seq, 2, "4 elements")?;
+        let d = next(&mut seq, 3, "4 elements")?;
+        Ok(Loc::new_at(a, b, c, d))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Loc, A::Error> {
+        let (mut from, mut to) = (None, None);
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::From => from = Some(map.next_value::<AnyPos>()?.0),
+                Field::To => to = Some(map.next_value::<AnyPos>()?.0),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let from = from.ok_or_else(|| de::Error::missing_field("from"))?;
+        let to = to.ok_or_else(|| de::Error::missing_field("to"))?;
+        Ok(Loc::new(from, to))
+    }
+}
@@ -0,0 +1,76 @@
+//! Strict serde form, use with `#[serde(with = "srcpos::serde_strict")]`
+//!
+//! Same as the derived form, but deserializing errors on unknown fields
+//! such as a typo'd `"collumn"`, also in the `Pos` fields of a `Loc`.
+//!
+//! # Examples
+//! ```
+//! # use srcpos::*;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     #[serde(with = "srcpos::serde_strict")]
+//!     start: Pos,
+//! }
+//! assert!(serde_json::from_str::<Config>(r#"{"start":{"line":1,"column":2}}"#).is_ok());
+//! assert!(serde_json::from_str::<Config>(r#"{"start":{"line":1,"collumn":2}}"#).is_err());
+//! ```
+
+use crate::{Loc, Pos};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Types that have a strict form
+pub trait Strict: Sized {
+    #[doc(hidden)]
+    fn deserialize_strict<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+}
+
+/// Serialize in the derived form
+#[inline]
+pub fn serialize<T: Serialize, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.serialize(serializer)
+}
+
+/// Deserialize from the derived form, erroring on unknown fields
+#[inline]
+pub fn deserialize<'de, T: Strict, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::deserialize_strict(deserializer)
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Pos", deny_unknown_fields)]
+struct StrictPos {
+    #[serde(alias = "row")]
+    line: usize,
+    #[serde(alias = "col")]
+    column: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Loc", deny_unknown_fields)]
+struct StrictLoc {
+    #[serde(alias = "start")]
+    from: StrictPos,
+    #[serde(alias = "end")]
+    to: StrictPos,
+}
+
+impl From<StrictPos> for Pos {
+    #[inline]
+    fn from(p: StrictPos) -> Self {
+        Pos::new(p.line, p.column)
+    }
+}
+
+impl Strict for Pos {
+    fn deserialize_strict<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        StrictPos::deserialize(deserializer).map(Pos::from)
+    }
+}
+
+impl Strict for Loc {
+    fn deserialize_strict<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let l = StrictLoc::deserialize(deserializer)?;
+        Ok(Loc::new(l.from.into(), l.to.into()))
+    }
+}
@@ -0,0 +1,143 @@
+//! `u32` packed [`SmallPos`] and [`SmallLoc`] for memory bound workloads
+//!
+//! `SmallPos` is 8 bytes and `SmallLoc` 16, half of [`Pos`] and [`Loc`] on 64 bit targets.  
+//! Widening with `From` is lossless, narrowing with `TryFrom` errors on values out of `u32`.
+//! Dummies map to dummies both ways, so a `Pos` of `u32::MAX` in both fields widens back as [`Pos::DUMMY`].
+//!
+//! # Examples
+//! ```
+//! # use srcpos::*;
+//! use core::convert::TryFrom;
+//!
+//! let small = SmallLoc::try_from(locof!(1, 2, 3, 4)).unwrap();
+//! assert_eq!(small.to_string(), "at 1:2 to 3:4");
+//! assert_eq!(Loc::from(small), locof!(1, 2, 3, 4));
+//! assert!(SmallPos::try_from(pos(1 << 32, 0)).is_err());
+//! ```
+
+use crate::{Loc, Pos};
+use core::convert::TryFrom;
+use core::fmt::{self, Display};
+use core::mem::size_of;
+use core::num::TryFromIntError;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const _: () = assert!(size_of::<SmallPos>() == 8);
+const _: () = assert!(size_of::<SmallLoc>() == 16);
+
+/// [`Pos`] with `u32` fields, see the [module docs](self)
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(not(feature = "compact-debug"), derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+#[repr(C)]
+pub struct SmallPos {
+    /// nth of line
+    #[cfg_attr(feature = "serde", serde(alias = "row"))]
+    pub line: u32,
+    /// nth of characters in current line
+    #[cfg_attr(feature = "serde", serde(alias = "col"))]
+    pub column: u32,
+}
+
+/// [`Loc`] with `u32` fields, see the [module docs](self)
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(not(feature = "compact-debug"), derive(Debug))]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+#[repr(C)]
+pub struct SmallLoc {
+    /// from
+    #[cfg_attr(feature = "serde", serde(alias = "start"))]
+    pub from: SmallPos,
+    /// to
+    #[cfg_attr(feature = "serde", serde(alias = "end"))]
+    pub to: SmallPos,
+}
+
+/// Items tying the small types to their wide type, the rest is from `pos_loc_common!`
+macro_rules! small_common {
+    ($small:ident, $wide:ident) => {
+        impl $small {
+            /// Widen to the
+            #[doc = concat!("[`", stringify!($wide), "`]")]
+            #[inline]
+            pub fn widen(self) -> $wide {
+                $wide::from(self)
+            }
+        }
+
+        /// Same as the
+        #[doc = concat!("[`", stringify!($wide), "`]")]
+        /// display
+        impl Display for $small {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Display::fmt(&self.widen(), f)
+            }
+        }
+
+        impl PartialEq<$wide> for $small {
+            #[inline]
+            fn eq(&self, other: &$wide) -> bool {
+                self.widen() == *other
+            }
+        }
+
+        impl PartialEq<$small> for $wide {
+            #[inline]
+            fn eq(&self, other: &$small) -> bool {
+                *self == other.widen()
+            }
+        }
+    };
+}
+
+pos_loc_common!(SmallPos, SmallLoc, u32);
+small_common!(SmallPos, Pos);
+small_common!(SmallLoc, Loc);
+
+impl From<SmallPos> for Pos {
+    #[inline]
+    fn from(pos: SmallPos) -> Self {
+        if pos.is_dummy() {
+            Pos::DUMMY
+        } else {
+            Pos::new(pos.line as usize, pos.column as usize)
+        }
+    }
+}
+
+impl TryFrom<Pos> for SmallPos {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(pos: Pos) -> Result<Self, Self::Error> {
+        if pos.is_dummy() {
+            return Ok(SmallPos::DUMMY);
+        }
+        Ok(SmallPos::new(
+            u32::try_from(pos.line)?,
+            u32::try_from(pos.column)?,
+        ))
+    }
+}
+
+impl From<SmallLoc> for Loc {
+    #[inline]
+    fn from(loc: SmallLoc) -> Self {
+        Loc::new(loc.from.into(), loc.to.into())
+    }
+}
+
+impl TryFrom<Loc> for SmallLoc {
+    type Error = TryFromIntError;
+
+    #[inline]
+    fn try_from(loc: Loc) -> Result<Self, Self::Error> {
+        Ok(SmallLoc::new(
+            SmallPos::try_from(loc.from)?,
+            SmallPos::try_from(loc.to)?,
+        ))
+    }
+}
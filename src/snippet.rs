@@ -0,0 +1,155 @@
+use crate::display::{gutter_width, snippet_last_line};
+use crate::source::cells;
+use crate::{Loc, SourceText};
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+use core::fmt::Display;
+
+/// rustc style snippet of a loc, the source lines after a gutter of line numbers and a caret underline
+///
+/// Output is one based, tabs print as four spaces like rustc.
+/// Multi line locs are drawn with rustc's `|` continuation on the left, every line of the loc is shown.
+/// An end at column 0 of a later line is the end of the previous line.
+///
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let source = SourceText::new("fn main() {\n    let x = y;\n}\n");
+/// let snippet = Snippet::new(&source, locof!(1, 12, 1, 13))
+///     .unwrap()
+///     .with_label("not found in this scope")
+///     .with_rustc_header("src/main.rs");
+/// assert_eq!(
+///     snippet.to_string(),
+///     " --> src/main.rs:2:13\n  |\n2 |     let x = y;\n  |             ^ not found in this scope"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snippet<'a> {
+    source: &'a SourceText<'a>,
+    loc: Loc,
+    label: &'a str,
+    header: Option<&'a str>,
+}
+
+impl<'a> Snippet<'a> {
+    /// Snippet of the loc in the source, `None` if the loc is reversed or not in the source
+    pub fn new(source: &'a SourceText<'a>, loc: Loc) -> Option<Self> {
+        loc.byte_range(source.index())?;
+        Some(Self {
+            source,
+            loc,
+            label: "",
+            header: None,
+        })
+    }
+
+    /// With the label after the underline
+    #[inline]
+    pub fn with_label(mut self, label: &'a str) -> Self {
+        self.label = label;
+        self
+    }
+
+    /// With the [`Loc::display_rustc`] arrow line and an empty gutter line before the source
+    #[inline]
+    pub fn with_rustc_header(mut self, file: &'a str) -> Self {
+        self.header = Some(file);
+        self
+    }
+
+    /// Text of the line without the line break
+    fn line(&self, line: usize) -> &'a str {
+        let text = self.source.line_text(line).unwrap_or("");
+        text.strip_suffix('\r').unwrap_or(text)
+    }
+}
+
+/// Terminal column of the char column, a tab is four cells
+fn cell_column(text: &str, column: usize) -> usize {
+    text.chars()
+        .take(column)
+        .map(|c| if c == '\t' { 4 } else { cells(c) })
+        .sum()
+}
+
+impl Display for Snippet<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Loc { from, to } = self.loc;
+        let last = snippet_last_line(&self.loc);
+        let width = gutter_width(&self.loc);
+        if let Some(file) = self.header {
+            write!(
+                f,
+                "{}\n{:width$} |",
+                self.loc.display_rustc(file),
+                "",
+                width = width
+            )?;
+        }
+        let mut first = self.header.is_none();
+        let mut row = |f: &mut fmt::Formatter<'_>, line: Option<usize>, content: &str| {
+            if !first {
+                f.write_str("\n")?;
+            }
+            first = false;
+            match line {
+                Some(line) => write!(f, "{:<width$} |", line.saturating_add(1), width = width)?,
+                None => write!(f, "{:width$} |", "", width = width)?,
+            }
+            let content = content.trim_end();
+            if content.is_empty() {
+                Ok(())
+            } else {
+                write!(f, " {}", content.replace('\t', "    "))
+            }
+        };
+        let label = if self.label.is_empty() {
+            String::new()
+        } else {
+            format!(" {}", self.label)
+        };
+
+        let text = self.line(from.line);
+        let start = cell_column(text, from.column);
+        if from.line == last {
+            let end = if to.line == last {
+                to.column
+            } else {
+                text.chars().count()
+            };
+            let len = cell_column(text, end).saturating_sub(start).max(1);
+            row(f, Some(last), text)?;
+            return row(
+                f,
+                None,
+                &format!(
+                    "{:start$}{:^<len$}{}",
+                    "",
+                    "",
+                    label,
+                    start = start,
+                    len = len
+                ),
+            );
+        }
+        if text.chars().take(from.column).all(char::is_whitespace) {
+            row(f, Some(from.line), &format!("/ {}", text))?;
+        } else {
+            row(f, Some(from.line), &format!("  {}", text))?;
+            row(f, None, &format!(" {:_<n$}^", "", n = start + 1))?;
+        }
+        for line in from.line + 1..=last {
+            row(f, Some(line), &format!("| {}", self.line(line)))?;
+        }
+        let text = self.line(last);
+        let end = if to.line == last {
+            to.column - 1
+        } else {
+            text.chars().count()
+        };
+        let n = cell_column(text, end) + 1;
+        row(f, None, &format!("|{:_<n$}^{}", "", label, n = n))
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{Loc, Pos};
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+
+impl Pos {
+    /// Zero based pos of the byte offset in the text, columns are counted in chars  
+    /// `None` if out of the text or not on a char boundary  
+    /// Scans the text, use [`LineIndex`](crate::LineIndex) for repeated lookups
+    pub fn from_offset(text: &str, offset: usize) -> Option<Pos> {
+        let before = text.get(..offset)?;
+        let (line, start) = match before.rfind('\n') {
+            Some(i) => (before.bytes().filter(|&b| b == b'\n').count(), i + 1),
+            None => (0, 0),
+        };
+        Some(Pos::new(line, before[start..].chars().count()))
+    }
+}
+
+/// Cursor over a source that steps chars forward and backward keeping the `Pos`,
+/// columns are counted in chars  
+/// Stepping back over a `\n` scans back to the previous line start
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let mut cursor = PosCursor::new("ab\nc");
+/// cursor.nth(2);
+/// assert_eq!(cursor.next(), Some(('c', pos(1, 0))));
+/// assert_eq!(cursor.prev(), Some(('c', pos(1, 0))));
+/// assert_eq!(cursor.prev(), Some(('\n', pos(0, 2))));
+/// assert_eq!(cursor.pos(), pos(0, 2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct PosCursor<'a> {
+    src: &'a str,
+    offset: usize,
+    pos: Pos,
+}
+
+impl<'a> PosCursor<'a> {
+    /// New at the start of the source
+    #[inline]
+    pub const fn new(src: &'a str) -> Self {
+        Self {
+            src,
+            offset: 0,
+            pos: Pos::zero(),
+        }
+    }
+
+    /// The source
+    #[inline]
+    pub const fn src(&self) -> &'a str {
+        self.src
+    }
+
+    /// Pos of the next char
+    #[inline]
+    pub const fn pos(&self) -> Pos {
+        self.pos
+    }
+
+    /// Byte offset of the next char
+    #[inline]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The next char without stepping
+    #[inline]
+    pub fn peek(&self) -> Option<char> {
+        self.src[self.offset..].chars().next()
+    }
+
+    /// Step back one char, returning it and its pos, which is the new current pos
+    pub fn prev(&mut self) -> Option<(char, Pos)> {
+        let c = self.src[..self.offset].chars().next_back()?;
+        self.offset -= c.len_utf8();
+        self.pos = if c == '\n' {
+            let before = &self.src[..self.offset];
+            let start = before.rfind('\n').map_or(0, |i| i + 1);
+            Pos::new(self.pos.line - 1, before[start..].chars().count())
+        } else {
+            self.pos.with_column(self.pos.column - 1)
+        };
+        Some((c, self.pos))
+    }
+}
+
+impl Iterator for PosCursor<'_> {
+    type Item = (char, Pos);
+
+    /// Step one char, returning it and its pos
+    fn next(&mut self) -> Option<(char, Pos)> {
+        let c = self.peek()?;
+        let at = self.pos;
+        self.offset += c.len_utf8();
+        self.pos = if c == '\n' {
+            Pos::new(at.line + 1, 0)
+        } else {
+            at.with_column(at.column + 1)
+        };
+        Some((c, at))
+    }
+}
+
+/// Terminal cells of the char, `1` without the `unicode-width` feature
+#[cfg(feature = "unicode-width")]
+pub(crate) fn cells(c: char) -> usize {
+    unicode_width::UnicodeWidthChar::width(c).unwrap_or(0)
+}
+/// Terminal cells of the char, `1` without the `unicode-width` feature
+#[cfg(all(feature = "alloc", not(feature = "unicode-width")))]
+pub(crate) fn cells(_: char) -> usize {
+    1
+}
+
+/// Terminal column of the char column in the line, tabs advance to the next multiple of `tab_width`  
+/// Double width chars such as CJK and emoji take two cells, combining marks none
+#[cfg(feature = "unicode-width")]
+pub fn visual_column(line: &str, column: usize, tab_width: usize) -> usize {
+    line.chars().take(column).fold(0, |at, c| {
+        if c == '\t' && tab_width > 0 {
+            (at / tab_width + 1) * tab_width
+        } else {
+            at + cells(c)
+        }
+    })
+}
+
+/// Word chars of [`Loc::snap_to_words`]
+fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl Loc {
+    /// Expand to whole words in the source, a word is a run of alphanumeric chars or `_`  
+    /// `from` moves left to the start of the word it is on,
+    /// `to` moves right to the end of the word it ends in,
+    /// an end in whitespace or punctuation (or out of the source) stays put
+    pub fn snap_to_words(&self, src: &str) -> Loc {
+        let from = match src.split('\n').nth(self.from.line) {
+            Some(line) if line.chars().nth(self.from.column).is_some_and(is_word) => {
+                let start = line
+                    .chars()
+                    .take(self.from.column)
+                    .enumerate()
+                    .filter(|&(_, c)| !is_word(c))
+                    .last()
+                    .map_or(0, |(i, _)| i + 1);
+                self.from.with_column(start)
+            }
+            _ => self.from,
+        };
+        let to = match src.split('\n').nth(self.to.line) {
+            Some(line)
+                if self.to.column > 0
+                    && line.chars().nth(self.to.column - 1).is_some_and(is_word) =>
+            {
+                let more = line
+                    .chars()
+                    .skip(self.to.column)
+                    .take_while(|&c| is_word(c))
+                    .count();
+                self.to.with_column(self.to.column + more)
+            }
+            _ => self.to,
+        };
+        Loc::new(from, to)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Loc {
+    /// Underline of the loc under line `line_no` with text `line_text`, see [`Loc::underline_in_with`]
+    #[inline]
+    pub fn underline_in(&self, line_text: &str, line_no: usize) -> Option<String> {
+        self.underline_in_with(line_text, line_no, '^')
+    }
+
+    /// Underline of the loc under line `line_no` with text `line_text`,
+    /// spaces up to the start then `mark` for each char, at least one  
+    /// Tabs before the start are kept as tabs so the underline aligns,
+    /// with the `unicode-width` feature chars take their terminal width  
+    /// Parts of multi line locs on other lines run to the line start or end  
+    /// `None` if the loc is not on the line or past its end
+    pub fn underline_in_with(&self, line_text: &str, line_no: usize, mark: char) -> Option<String> {
+        if line_no < self.from.line || self.to.line < line_no {
+            return None;
+        }
+        let len = line_text.chars().count();
+        let start = if self.from.line == line_no {
+            self.from.column
+        } else {
+            0
+        };
+        let end = if self.to.line == line_no {
+            self.to.column
+        } else {
+            len
+        };
+        if start > len || end > len {
+            return None;
+        }
+        let mut out = String::new();
+        for c in line_text.chars().take(start) {
+            if c == '\t' {
+                out.push('\t');
+            } else {
+                out.extend(core::iter::repeat(' ').take(cells(c)));
+            }
+        }
+        let width: usize = line_text.chars().take(end).skip(start).map(cells).sum();
+        out.extend(core::iter::repeat(mark).take(width.max(1)));
+        Some(out)
+    }
+}
@@ -0,0 +1,55 @@
+use crate::display::UNKNOWN;
+use crate::Pos;
+use core::fmt;
+use core::fmt::Display;
+
+/// Pos in a source file, see [`here!`](crate::here)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct SourceLocation {
+    /// file path
+    pub file: &'static str,
+    /// pos in file
+    pub pos: Pos,
+}
+impl SourceLocation {
+    /// New at
+    #[inline]
+    pub const fn new(file: &'static str, pos: Pos) -> Self {
+        Self { file, pos }
+    }
+}
+
+/// `file:line:column`
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.pos.is_dummy() {
+            return write!(f, "{}:{}", self.file, UNKNOWN);
+        }
+        write!(f, "{}:{}:{}", self.file, self.pos.line, self.pos.column)
+    }
+}
+
+impl From<SourceLocation> for Pos {
+    #[inline]
+    fn from(s: SourceLocation) -> Self {
+        s.pos
+    }
+}
+
+/// Expands to the [`SourceLocation`] on which it was invoked.
+/// ## See
+/// - [`file!`](https://doc.rust-lang.org/std/macro.file.html)
+/// - [`pos!`](crate::pos!)
+/// # Examples
+/// ```rust
+/// # use srcpos::*;
+/// static HERE: SourceLocation = here!();
+/// assert_eq!(HERE.file, file!());
+/// println!("defined at {}", HERE);
+/// ```
+#[macro_export]
+macro_rules! here {
+    () => {
+        $crate::SourceLocation::new(file!(), $crate::pos!())
+    };
+}
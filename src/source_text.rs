@@ -0,0 +1,43 @@
+use crate::{LineIndex, Loc};
+
+/// Source text with its [`LineIndex`]
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct SourceText<'a> {
+    text: &'a str,
+    index: LineIndex,
+}
+
+impl<'a> SourceText<'a> {
+    /// Index the text
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            text,
+            index: LineIndex::new(text),
+        }
+    }
+
+    /// The text
+    #[inline]
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// The line index
+    #[inline]
+    pub fn index(&self) -> &LineIndex {
+        &self.index
+    }
+
+    /// Text of the line, without the `\n`
+    #[inline]
+    pub fn line_text(&self, line: usize) -> Option<&'a str> {
+        self.text.get(self.index.line_range(line)?)
+    }
+
+    /// Text the loc covers, across lines if needed, `""` for an empty loc  
+    /// `None` if the loc is reversed or not in the text
+    #[inline]
+    pub fn text_of(&self, loc: Loc) -> Option<&'a str> {
+        self.text.get(loc.byte_range(&self.index)?)
+    }
+}
@@ -0,0 +1,99 @@
+use crate::Loc;
+use core::fmt;
+use core::fmt::{Debug, Display};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "bincode")]
+use bincode::{Decode, Encode};
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+
+#[cfg(feature = "rkyv")]
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+
+/// Value with its location in source code
+///
+/// See [`spanned_flat`](crate::spanned_flat) and [`spanned_value_only`](crate::spanned_value_only)
+/// for other serde forms
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[cfg_attr(feature = "bincode", derive(Encode, Decode))]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(Archive, RkyvSerialize, RkyvDeserialize),
+    archive(check_bytes)
+)]
+#[derive(PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord)]
+pub struct Spanned<T> {
+    /// value
+    pub value: T,
+    /// location
+    pub loc: Loc,
+}
+impl<T> Spanned<T> {
+    /// New at
+    #[inline]
+    pub const fn new(value: T, loc: Loc) -> Self {
+        Self { value, loc }
+    }
+    /// New at [`Loc::DUMMY`]
+    #[inline]
+    pub const fn dummy(value: T) -> Self {
+        Self::new(value, Loc::DUMMY)
+    }
+    /// Take the value
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+    /// Borrow the value
+    #[inline]
+    pub const fn as_ref(&self) -> Spanned<&T> {
+        Spanned::new(&self.value, self.loc)
+    }
+    /// Map the value, keep the loc
+    #[inline]
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U> {
+        Spanned::new(f(self.value), self.loc)
+    }
+}
+
+/// Default value at [`Loc::DUMMY`], the placeholder for a value without a known loc,
+/// unlike [`Loc::default`] which is a real loc at `0:0`
+impl<T: Default> Default for Spanned<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::dummy(T::default())
+    }
+}
+
+impl<T> From<(T, Loc)> for Spanned<T> {
+    #[inline]
+    fn from((value, loc): (T, Loc)) -> Self {
+        Self::new(value, loc)
+    }
+}
+
+/// `value (at 1:2-3:4)`
+impl<T: Display> Display for Spanned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.loc.is_dummy() {
+            write!(f, "{} ({})", self.value, self.loc.bare())
+        } else {
+            write!(f, "{} (at {})", self.value, self.loc.bare())
+        }
+    }
+}
+
+/// `Spanned(1:2-3:4, value)`, `{:#?}` pretty prints the value
+impl<T: Debug> Debug for Spanned<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Spanned")
+            .field(&format_args!("{}", self.loc.bare()))
+            .field(&self.value)
+            .finish()
+    }
+}
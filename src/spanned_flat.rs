@@ -0,0 +1,56 @@
+//! Serde form of [`Spanned`] with the `loc` next to the value's fields,
+//! use with `#[serde(with = "srcpos::spanned_flat")]`
+//!
+//! The value must serialize as a map, nested spanned values would repeat the `loc` key.
+//!
+//! # Examples
+//! ```
+//! # use srcpos::*;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Ident {
+//!     name: String,
+//! }
+//! #[derive(Serialize, Deserialize)]
+//! struct Node {
+//!     #[serde(with = "srcpos::spanned_flat")]
+//!     ident: Spanned<Ident>,
+//! }
+//! ```
+
+use crate::{Loc, Spanned};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+struct FlatRef<'a, T> {
+    #[serde(flatten)]
+    value: &'a T,
+    loc: &'a Loc,
+}
+
+#[derive(Deserialize)]
+struct Flat<T> {
+    #[serde(flatten)]
+    value: T,
+    loc: Loc,
+}
+
+/// Serialize with the loc flattened into the value
+pub fn serialize<T: Serialize, S: Serializer>(
+    spanned: &Spanned<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    FlatRef {
+        value: &spanned.value,
+        loc: &spanned.loc,
+    }
+    .serialize(serializer)
+}
+
+/// Deserialize with the loc flattened into the value
+pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Spanned<T>, D::Error> {
+    let Flat { value, loc } = Flat::deserialize(deserializer)?;
+    Ok(Spanned::new(value, loc))
+}
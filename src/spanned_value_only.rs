@@ -0,0 +1,68 @@
+//! Serde form of [`Spanned`] without the loc, use with `#[serde(with = "srcpos::spanned_value_only")]`
+//!
+//! Only the value is serialized, the loc is [`Loc::DUMMY`] after deserializing,
+//! the same as [`Spanned::default`] gives.
+//! [`ValueOnly`] does the same as a type, for places a `with` attribute can't reach such as `Vec<ValueOnly<T>>`.
+//!
+//! # Examples
+//! ```
+//! # use srcpos::*;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Node {
+//!     #[serde(with = "srcpos::spanned_value_only")]
+//!     name: Spanned<u32>,
+//! }
+//! ```
+
+use crate::{Loc, Spanned};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serialize only the value
+#[inline]
+pub fn serialize<T: Serialize, S: Serializer>(
+    spanned: &Spanned<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    spanned.value.serialize(serializer)
+}
+
+/// Deserialize the value at [`Loc::DUMMY`]
+#[inline]
+pub fn deserialize<'de, T: Deserialize<'de>, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Spanned<T>, D::Error> {
+    T::deserialize(deserializer).map(|value| Spanned::new(value, Loc::DUMMY))
+}
+
+/// [`Spanned`] serializing as only its value, see the [module docs](self)
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, PartialOrd, Ord, Default)]
+pub struct ValueOnly<T>(pub Spanned<T>);
+
+impl<T> From<Spanned<T>> for ValueOnly<T> {
+    #[inline]
+    fn from(spanned: Spanned<T>) -> Self {
+        Self(spanned)
+    }
+}
+
+impl<T> From<ValueOnly<T>> for Spanned<T> {
+    #[inline]
+    fn from(value_only: ValueOnly<T>) -> Self {
+        value_only.0
+    }
+}
+
+impl<T: Serialize> Serialize for ValueOnly<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ValueOnly<T> {
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer).map(Self)
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{Loc, Pos};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::iter::FromIterator;
+
+impl Loc {
+    /// Merge overlapping and touching locs, sorted by `from`
+    pub fn merge_overlapping(locs: impl IntoIterator<Item = Loc>) -> Vec<Loc> {
+        let mut locs: Vec<Loc> = locs.into_iter().map(Loc::normalize).collect();
+        locs.sort_unstable();
+        let mut merged: Vec<Loc> = Vec::with_capacity(locs.len());
+        for loc in locs {
+            match merged.last_mut() {
+                Some(last) if loc.from <= last.to => *last = last.merge(&loc),
+                _ => merged.push(loc),
+            }
+        }
+        merged
+    }
+
+    /// How many spans touch each line, a span counts for every line of [`Loc::lines`]  
+    /// Lines no span touches are left out, spans with a [`Pos::DUMMY`] end count for no line
+    pub fn per_line_counts(spans: &[Loc]) -> BTreeMap<usize, usize> {
+        let mut counts = BTreeMap::new();
+        for line in spans.iter().flat_map(Loc::lines) {
+            *counts.entry(line).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// Sweep over overlapping locs, giving the disjoint sub locs between all the ends
+/// together with the locs active in each
+///
+/// Sub locs come in order, those with no active loc are skipped.
+/// Locs are half open, so at a shared boundary the ending loc is left before the starting one is entered
+/// and they are never active together. Empty locs are never active.
+/// Active indices are into the given slice, in ascending order.
+///
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let spans = [locof!(0, 0, 0, 6), locof!(0, 4, 1, 2)];
+/// let mut sweep = SpanSweep::new(&spans);
+/// assert_eq!(sweep.next_interval(), Some((locof!(0, 0, 0, 4), &[0][..])));
+/// assert_eq!(sweep.next_interval(), Some((locof!(0, 4, 0, 6), &[0, 1][..])));
+/// assert_eq!(sweep.next_interval(), Some((locof!(0, 6, 1, 2), &[1][..])));
+/// assert_eq!(sweep.next_interval(), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpanSweep {
+    /// `(at, is_start, index)`, ends sort before starts at the same pos
+    events: Vec<(Pos, bool, usize)>,
+    next: usize,
+    active: Vec<usize>,
+}
+impl SpanSweep {
+    /// New over the locs, reversed locs are normalized
+    pub fn new(spans: &[Loc]) -> Self {
+        let mut events = Vec::with_capacity(spans.len() * 2);
+        for (i, loc) in spans.iter().enumerate() {
+            let loc = loc.normalize();
+            if loc.from != loc.to {
+                events.push((loc.from, true, i));
+                events.push((loc.to, false, i));
+            }
+        }
+        events.sort_unstable();
+        Self {
+            events,
+            next: 0,
+            active: Vec::new(),
+        }
+    }
+
+    /// Next sub loc and the indices of the locs active in it
+    pub fn next_interval(&mut self) -> Option<(Loc, &[usize])> {
+        loop {
+            let at = self.events.get(self.next)?.0;
+            while let Some(&(pos, start, i)) = self.events.get(self.next) {
+                if pos != at {
+                    break;
+                }
+                match (start, self.active.binary_search(&i)) {
+                    (true, Err(slot)) => self.active.insert(slot, i),
+                    (false, Ok(slot)) => {
+                        self.active.remove(slot);
+                    }
+                    _ => {}
+                }
+                self.next += 1;
+            }
+            let to = self.events.get(self.next)?.0;
+            if !self.active.is_empty() {
+                return Some((Loc::new(at, to), &self.active));
+            }
+        }
+    }
+}
+
+/// Set of source regions, kept as sorted disjoint locs  
+/// Overlapping and touching locs merge on insert, empty locs cover nothing
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct LocSet {
+    locs: Vec<Loc>,
+}
+
+impl LocSet {
+    /// New empty
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a loc
+    pub fn insert(&mut self, loc: Loc) {
+        let loc = loc.normalize();
+        if loc.from == loc.to {
+            return;
+        }
+        let lo = self.locs.partition_point(|l| l.to < loc.from);
+        let hi = self.locs.partition_point(|l| l.from <= loc.to);
+        let merged = self.locs[lo..hi].iter().fold(loc, |m, l| m.merge(l));
+        self.locs.splice(lo..hi, Some(merged));
+    }
+
+    /// The disjoint locs, sorted
+    #[inline]
+    pub fn locs(&self) -> &[Loc] {
+        &self.locs
+    }
+
+    /// Is the pos in the set
+    pub fn contains(&self, pos: Pos) -> bool {
+        let i = self.locs.partition_point(|l| l.to <= pos);
+        self.locs.get(i).is_some_and(|l| l.contains(pos))
+    }
+
+    /// Zero based lines with any covered char, ascending  
+    /// A loc ending at column 0 does not cover that line, a loc ending at [`Pos::DUMMY`] covers no line
+    pub fn covered_lines(&self) -> impl Iterator<Item = usize> + '_ {
+        let mut next = 0;
+        self.locs
+            .iter()
+            .filter(|l| !l.to.is_dummy())
+            .flat_map(move |l| {
+                let end = if l.to.column == 0 {
+                    l.to.line
+                } else {
+                    l.to.line.saturating_add(1)
+                };
+                let lines = l.from.line.max(next)..end;
+                next = next.max(end);
+                lines
+            })
+    }
+
+    /// Share of the first `total_lines` lines covered, in `0.0..=1.0`, `0.0` if `total_lines` is 0
+    pub fn coverage_of(&self, total_lines: usize) -> f64 {
+        if total_lines == 0 {
+            return 0.0;
+        }
+        let hit = self
+            .covered_lines()
+            .take_while(|&l| l < total_lines)
+            .count();
+        hit as f64 / total_lines as f64
+    }
+}
+
+impl FromIterator<Loc> for LocSet {
+    fn from_iter<I: IntoIterator<Item = Loc>>(iter: I) -> Self {
+        Self {
+            locs: Loc::merge_overlapping(iter.into_iter().filter(|l| l.from != l.to)),
+        }
+    }
+}
+
+/// Locs keyed by `K`, for "which named region is here" lookups
+/// # Examples
+/// ```
+/// # use srcpos::*;
+/// let mut map = SpanMap::new();
+/// map.insert("/items/0", locof!(1, 0, 4, 1));
+/// map.insert("/items/0/name", locof!(1, 4, 1, 9));
+/// map.insert("/items/1", locof!(5, 0, 6, 1));
+/// let here: Vec<_> = map.containing(pos(1, 5)).map(|(k, _)| *k).collect();
+/// assert_eq!(here, ["/items/0", "/items/0/name"]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct SpanMap<K: Ord> {
+    map: BTreeMap<K, Loc>,
+}
+
+impl<K: Ord> SpanMap<K> {
+    /// New empty
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            map: BTreeMap::new(),
+        }
+    }
+
+    /// Insert the loc of the key, returns the loc it had
+    #[inline]
+    pub fn insert(&mut self, key: K, loc: Loc) -> Option<Loc> {
+        self.map.insert(key, loc)
+    }
+
+    /// Loc of the key
+    #[inline]
+    pub fn get(&self, key: &K) -> Option<&Loc> {
+        self.map.get(key)
+    }
+
+    /// Remove the key, returns its loc
+    #[inline]
+    pub fn remove(&mut self, key: &K) -> Option<Loc> {
+        self.map.remove(key)
+    }
+
+    /// Count of keys
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Keys and locs in key order
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &Loc)> {
+        self.map.iter()
+    }
+
+    /// Keys and locs that contain the pos as [`Loc::contains`], in key order  
+    /// Scans every entry
+    pub fn containing(&self, pos: Pos) -> impl Iterator<Item = (&K, &Loc)> {
+        self.map.iter().filter(move |(_, l)| l.contains(pos))
+    }
+}
+
+impl<K: Ord> Default for SpanMap<K> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> FromIterator<(K, Loc)> for SpanMap<K> {
+    fn from_iter<I: IntoIterator<Item = (K, Loc)>>(iter: I) -> Self {
+        Self {
+            map: iter.into_iter().collect(),
+        }
+    }
+}
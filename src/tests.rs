@@ -24,3 +24,2913 @@ fn test_pos_macro() {
     let b = posof!([1, 2]);
     assert_eq!(a, b);
 }
+
+mod prelude_only {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_prelude() {
+        let p: Pos = posof!(1, 2);
+        let l: Loc = loc(p, pos(3, 4));
+        assert_eq!(l, locof!(1, 2, 3, 4));
+        assert_eq!(pos!().line, line!() as usize);
+    }
+}
+
+mod quickcheck_props {
+    use crate::*;
+    use core::cmp::Ordering;
+    use quickcheck::quickcheck;
+    #[cfg(feature = "quickcheck")]
+    use quickcheck::Arbitrary;
+
+    /// `(from.line, from.column, to.line, to.column)`, so the props run without the `Arbitrary` impls
+    type Fields = (usize, usize, usize, usize);
+
+    #[cfg(feature = "quickcheck")]
+    #[test]
+    fn prop_loc_ordered() {
+        fn prop(l: Loc) -> bool {
+            l.from <= l.to && l.shrink().all(|s| s.from <= s.to)
+        }
+        quickcheck(prop as fn(Loc) -> bool);
+    }
+
+    #[test]
+    fn prop_loc_array_round_trip() {
+        fn prop(l: Fields) -> bool {
+            let l = Loc::from(l);
+            let a: [usize; 4] = l.into();
+            Loc::from(a) == l
+        }
+        quickcheck(prop as fn(Fields) -> bool);
+    }
+
+    #[test]
+    fn prop_loc_ord_antisymmetric() {
+        fn prop(a: Fields, b: Fields) -> bool {
+            let (a, b) = (Loc::from(a), Loc::from(b));
+            a.total_cmp(&b) == b.total_cmp(&a).reverse()
+                && (a.total_cmp(&b) == Ordering::Equal) == (a == b)
+        }
+        quickcheck(prop as fn(Fields, Fields) -> bool);
+    }
+
+    #[test]
+    fn prop_loc_ord_transitive() {
+        fn prop(a: Fields, b: Fields, c: Fields) -> bool {
+            let (a, b, c) = (Loc::from(a), Loc::from(b), Loc::from(c));
+            !(a <= b && b <= c) || a <= c
+        }
+        quickcheck(prop as fn(Fields, Fields, Fields) -> bool);
+    }
+
+    #[test]
+    fn prop_loc_ord_lexicographic() {
+        fn prop(a: Fields, b: Fields) -> bool {
+            let (a, b) = (Loc::from(a), Loc::from(b));
+            a.total_cmp(&b) == (a.from, a.to).cmp(&(b.from, b.to))
+                && a.from.cmp(&b.from)
+                    == (a.from.line, a.from.column).cmp(&(b.from.line, b.from.column))
+        }
+        quickcheck(prop as fn(Fields, Fields) -> bool);
+    }
+
+    #[cfg(feature = "alloc")]
+    const PIECES: [&str; 7] = ["a", "bc", "\n", "\r\n", "\u{e9}", "\u{4e2d}", "\u{1f980}"];
+
+    #[cfg(feature = "alloc")]
+    fn text_of(pieces: &[u8]) -> String {
+        pieces
+            .iter()
+            .map(|&p| PIECES[p as usize % PIECES.len()])
+            .collect()
+    }
+
+    /// Char boundary picked by the seed, including 0 and the end
+    #[cfg(feature = "alloc")]
+    fn boundary(text: &str, seed: u8) -> usize {
+        let bounds: Vec<usize> = (0..=text.len())
+            .filter(|&i| text.is_char_boundary(i))
+            .collect();
+        bounds[seed as usize % bounds.len()]
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn prop_line_index_apply_edit() {
+        fn prop(doc: Vec<u8>, edits: Vec<(u8, u8, Vec<u8>)>) -> bool {
+            let mut text = text_of(&doc);
+            let mut index = LineIndex::new(&text);
+            for (a, b, new) in edits {
+                let (a, b) = (boundary(&text, a), boundary(&text, b));
+                let range = a.min(b)..a.max(b);
+                let new = text_of(&new);
+                index.apply_edit(range.clone(), &new);
+                text.replace_range(range, &new);
+                if index != LineIndex::new(&text) {
+                    return false;
+                }
+            }
+            true
+        }
+        quickcheck(prop as fn(Vec<u8>, Vec<(u8, u8, Vec<u8>)>) -> bool);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn prop_line_index_apply_edits() {
+        fn prop(doc: Vec<u8>, edits: Vec<(u8, u8, Vec<u8>)>) -> bool {
+            let text = text_of(&doc);
+            let mut bounds: Vec<usize> = edits
+                .iter()
+                .flat_map(|&(a, b, _)| vec![boundary(&text, a), boundary(&text, b)])
+                .collect();
+            bounds.sort_unstable();
+            let batch: Vec<(Range<usize>, String)> = bounds
+                .chunks(2)
+                .zip(&edits)
+                .map(|(r, (_, _, new))| (r[0]..r[1], text_of(new)))
+                .collect();
+            let mut edited = String::new();
+            let mut at = 0;
+            for (range, new) in &batch {
+                edited.push_str(&text[at..range.start]);
+                edited.push_str(new);
+                at = range.end;
+            }
+            edited.push_str(&text[at..]);
+            let mut index = LineIndex::new(&text);
+            index.apply_edits(batch.iter().map(|(r, new)| (r.clone(), new.as_str())));
+            index == LineIndex::new(&edited)
+        }
+        quickcheck(prop as fn(Vec<u8>, Vec<(u8, u8, Vec<u8>)>) -> bool);
+    }
+
+    #[test]
+    fn prop_compact_loc_round_trip() {
+        fn prop(a: u32, b: u16, c: u32, d: u16) -> bool {
+            let line = a as usize % (CompactLoc::MAX_LINE + 1);
+            let delta = c as usize % (CompactLoc::MAX_LINE_DELTA + 1);
+            let l = Loc::new_at(
+                line,
+                b as usize % (CompactLoc::MAX_COLUMN + 1),
+                line + delta,
+                d as usize % (CompactLoc::MAX_COLUMN + 1),
+            );
+            CompactLoc::try_pack(l).map(CompactLoc::unpack) == Some(l)
+        }
+        quickcheck(prop as fn(u32, u16, u32, u16) -> bool);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn prop_line_index_parallel() {
+        fn prop(doc: Vec<u8>, repeat: u8) -> bool {
+            let text = text_of(&doc).repeat(repeat as usize * 64);
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(4)
+                .build()
+                .unwrap();
+            pool.install(|| LineIndex::new_parallel(&text)) == LineIndex::new(&text)
+        }
+        quickcheck(prop as fn(Vec<u8>, u8) -> bool);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn prop_line_index_all_parallel() {
+        fn prop(docs: Vec<Vec<u8>>) -> bool {
+            let texts: Vec<String> = docs.iter().map(|d| text_of(d)).collect();
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(4)
+                .build()
+                .unwrap();
+            pool.install(|| LineIndex::index_all_parallel(&texts))
+                == texts.iter().map(|t| LineIndex::new(t)).collect::<Vec<_>>()
+        }
+        quickcheck(prop as fn(Vec<Vec<u8>>) -> bool);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn prop_offset_pos_round_trip() {
+        fn prop(doc: Vec<u8>) -> bool {
+            let text = text_of(&doc);
+            let index = LineIndex::new(&text);
+            let mut cursor = index.cursor();
+            for offset in 0..=text.len() + 1 {
+                let p = index.pos_of(offset);
+                if p != Pos::from_offset(&text, offset) || p != cursor.pos_of(offset) {
+                    return false;
+                }
+                let on_boundary = offset <= text.len() && text.is_char_boundary(offset);
+                if p.is_some() != on_boundary
+                    || p.and_then(|p| index.offset_of(p)).is_some() != on_boundary
+                {
+                    return false;
+                }
+                if let Some(p) = p {
+                    if index.offset_of(p) != Some(offset) {
+                        return false;
+                    }
+                }
+            }
+            for line in 0..index.line_count() {
+                let len = index.line_len(line).unwrap();
+                for column in 0..=len {
+                    let p = pos(line, column);
+                    if index.offset_of(p).and_then(|o| index.pos_of(o)) != Some(p) {
+                        return false;
+                    }
+                }
+                if index.offset_of(pos(line, len + 1)).is_some() {
+                    return false;
+                }
+            }
+            index.offset_of(index.end_pos()) == Some(text.len())
+                && index.offset_of(pos(index.line_count(), 0)).is_none()
+        }
+        quickcheck(prop as fn(Vec<u8>) -> bool);
+    }
+
+    #[cfg(feature = "quickcheck")]
+    #[test]
+    fn test_pos_shrink_line_first() {
+        let mut s = pos(4, 4).shrink();
+        assert_eq!(s.next().map(|p| p.column), Some(4));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_line_index() {
+    let index = LineIndex::new("ab\nc\u{e9}d\n\u{4e2d}");
+    assert_eq!(index.line_count(), 3);
+    assert_eq!(index.pos_of(0), Some(pos(0, 0)));
+    assert_eq!(index.pos_of(2), Some(pos(0, 2)));
+    assert_eq!(index.pos_of(3), Some(pos(1, 0)));
+    assert_eq!(index.pos_of(5), None);
+    assert_eq!(index.pos_of(6), Some(pos(1, 2)));
+    assert_eq!(index.pos_of(11), Some(pos(2, 1)));
+    assert_eq!(index.pos_of(12), None);
+    assert_eq!(index.offset_of(pos(1, 2)), Some(6));
+    assert_eq!(index.offset_of(pos(1, 3)), Some(7));
+    assert_eq!(index.offset_of(pos(1, 4)), None);
+    assert_eq!(index.offset_of(pos(2, 1)), Some(11));
+    assert_eq!(index.offset_of(pos(3, 0)), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_line_len() {
+    let index = LineIndex::new("ab\nc\u{e9}d\n\n\u{4e2d}x");
+    assert_eq!(index.line_len(0), Some(2));
+    assert_eq!(index.line_len(1), Some(3));
+    assert_eq!(index.line_len_bytes(1), Some(4));
+    assert_eq!(index.line_len(2), Some(0));
+    assert_eq!(index.line_len(3), Some(2));
+    assert_eq!(index.line_len_bytes(3), Some(4));
+    assert_eq!(index.line_len(4), None);
+
+    assert_eq!(pos(0, 9).clamp_column(&index), pos(0, 2));
+    assert_eq!(pos(1, 1).clamp_column(&index), pos(1, 1));
+    assert_eq!(pos(2, 5).clamp_column(&index), pos(2, 0));
+    assert_eq!(pos(3, 7).clamp_column(&index), pos(3, 2));
+    assert_eq!(pos(8, 0).clamp_column(&index), pos(3, 2));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_loc_similarity() {
+    let index = LineIndex::new("0123456789");
+    let a = locof!(0, 0, 0, 6);
+    let b = locof!(0, 2, 0, 8);
+    assert_eq!(a.intersection(&b), Some(locof!(0, 2, 0, 6)));
+    assert_eq!(a.overlap_len(&b, &index), 4);
+    assert_eq!(a.similarity(&b, &index), 0.5);
+    assert_eq!(a.similarity(&a, &index), 1.0);
+    let c = locof!(0, 8, 0, 9);
+    assert_eq!(a.intersection(&c), None);
+    assert_eq!(a.similarity(&c, &index), 0.0);
+    let p = Loc::from(pos(0, 3));
+    assert_eq!(p.similarity(&p, &index), 1.0);
+    assert_eq!(p.similarity(&Loc::from(pos(0, 4)), &index), 0.0);
+    let outside = locof!(0, 0, 3, 0);
+    assert_eq!(outside.similarity(&a, &index), 0.0);
+    assert_eq!(a.similarity(&outside, &index), 0.0);
+}
+
+#[test]
+fn test_loc_merge_contains() {
+    let a = locof!(1, 2, 1, 6);
+    let b = locof!(1, 4, 3, 0);
+    let m = a.merge(&b);
+    assert_eq!(m, locof!(1, 2, 3, 0));
+    assert!(m.contains_loc(&a));
+    assert!(m.contains_loc(&b));
+    assert!(!a.contains_loc(&b));
+    assert!(a.contains(pos(1, 2)));
+    assert!(!a.contains(pos(1, 6)));
+}
+
+#[cfg(feature = "rkyv")]
+#[test]
+fn test_rkyv_archived_query() {
+    let locs = vec![locof!(0, 0, 0, 4), locof!(1, 2, 3, 4)];
+    let bytes = rkyv::to_bytes::<_, 256>(&locs).unwrap();
+    let archived = rkyv::check_archived_root::<Vec<Loc>>(&bytes).unwrap();
+    assert_eq!(archived.len(), 2);
+    assert_eq!(archived[1].to_loc(), locs[1]);
+    assert!(archived[1].contains(pos(2, 0)));
+    assert!(archived[1].contains_loc(&locof!(1, 3, 1, 4)));
+    assert_eq!(archived[0].merge(&locs[1]), locof!(0, 0, 3, 4));
+    let back: Vec<Loc> = rkyv::Deserialize::deserialize(archived, &mut rkyv::Infallible).unwrap();
+    assert_eq!(back, locs);
+}
+
+#[test]
+fn test_from_narrow_ints() {
+    let (l, c): (u32, u32) = (3, 4);
+    assert_eq!(Pos::from_u32(l, c), pos(3, 4));
+    assert_eq!(Pos::from_u16(3, 4), pos(3, 4));
+    assert_eq!(Pos::from_u32(u32::MAX, 0).line, u32::MAX as usize);
+    assert_eq!(Loc::from_u32(1, 2, 3, 4), locof!(1, 2, 3, 4));
+}
+
+#[cfg(feature = "borsh")]
+#[test]
+fn test_borsh() {
+    let l = locof!(1, 2, 3, 0x0104);
+    let bytes = borsh::to_vec(&l).unwrap();
+    #[rustfmt::skip]
+    assert_eq!(bytes, [
+        1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0,
+        3, 0, 0, 0, 0, 0, 0, 0, 4, 1, 0, 0, 0, 0, 0, 0,
+    ]);
+    assert_eq!(borsh::from_slice::<Loc>(&bytes).unwrap(), l);
+    assert_eq!(borsh::from_slice::<Pos>(&bytes[..16]).unwrap(), pos(1, 2));
+    assert!(borsh::from_slice::<Loc>(&bytes[..31]).is_err());
+}
+
+#[test]
+fn test_loc_anchors() {
+    const L: Loc = Loc::new_at(1, 2, 3, 4);
+    const S: Loc = L.start_loc();
+    assert_eq!(S, locof!(1, 2, 1, 2));
+    assert_eq!(L.end_loc(), locof!(3, 4, 3, 4));
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn test_bincode() {
+    let config = bincode::config::standard();
+    let l = locof!(1, 2, 300, 4);
+    let bytes = bincode::encode_to_vec(l, config).unwrap();
+    assert_eq!(bytes.len(), 6);
+    assert_eq!(bincode::encode_to_vec(pos(1, 2), config).unwrap(), [1, 2]);
+    let (back, read): (Loc, usize) = bincode::decode_from_slice(&bytes, config).unwrap();
+    assert_eq!(back, l);
+    assert_eq!(read, bytes.len());
+    assert!(bincode::decode_from_slice::<Loc, _>(&bytes[..5], config).is_err());
+}
+
+#[test]
+fn test_approx_eq() {
+    assert!(pos(3, 4).approx_eq(&pos(3, 5), 0, 1));
+    assert!(!pos(3, 4).approx_eq(&pos(3, 6), 0, 1));
+    assert!(!pos(3, 4).approx_eq(&pos(4, 4), 0, 1));
+    assert!(pos(0, 0).approx_eq(&pos(0, 1), 0, 1) && pos(0, 1).approx_eq(&pos(0, 2), 0, 1));
+    assert!(!pos(0, 0).approx_eq(&pos(0, 2), 0, 1));
+    assert!(locof!(1, 2, 3, 4).approx_eq(&locof!(1, 3, 3, 3), 0, 1));
+    assert!(!locof!(1, 2, 3, 4).approx_eq(&locof!(1, 3, 4, 3), 0, 1));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_strict() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Token {
+        #[serde(with = "crate::serde_strict")]
+        pos: Pos,
+        #[serde(with = "crate::serde_strict")]
+        loc: Loc,
+    }
+
+    let t = Token {
+        pos: pos(1, 2),
+        loc: locof!(1, 2, 3, 4),
+    };
+    let json = serde_json::to_string(&t).unwrap();
+    assert_eq!(
+        json,
+        format!(
+            r#"{{"pos":{},"loc":{}}}"#,
+            serde_json::to_string(&t.pos).unwrap(),
+            serde_json::to_string(&t.loc).unwrap()
+        )
+    );
+    assert_eq!(serde_json::from_str::<Token>(&json).unwrap(), t);
+    let aliased = r#"{"pos":{"row":1,"col":2},"loc":{"start":{"line":1,"column":2},"end":{"line":3,"column":4}}}"#;
+    assert_eq!(serde_json::from_str::<Token>(aliased).unwrap(), t);
+
+    let typo = r#"{"pos":{"line":1,"collumn":2},"loc":{"from":{"line":1,"column":2},"to":{"line":3,"column":4}}}"#;
+    let err = serde_json::from_str::<Token>(typo).unwrap_err().to_string();
+    assert!(err.contains("unknown field `collumn`"), "{}", err);
+    let nested = r#"{"pos":{"line":1,"column":2},"loc":{"from":{"line":1,"column":2,"x":0},"to":{"line":3,"column":4}}}"#;
+    assert!(serde_json::from_str::<Token>(nested).is_err());
+    let extra = r#"{"pos":{"line":1,"column":2},"loc":{"from":{"line":1,"column":2},"to":{"line":3,"column":4},"file":"a"}}"#;
+    assert!(serde_json::from_str::<Token>(extra).is_err());
+    assert!(serde_json::from_str::<Pos>(r#"{"line":1,"column":2,"x":0}"#).is_ok());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_compact() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Token {
+        #[serde(with = "crate::serde_compact")]
+        pos: Pos,
+        #[serde(with = "crate::serde_compact")]
+        loc: Loc,
+    }
+
+    let t = Token {
+        pos: pos(1, 2),
+        loc: locof!(1, 2, 3, 4),
+    };
+    let json = serde_json::to_string(&t).unwrap();
+    assert_eq!(json, r#"{"pos":[1,2],"loc":[1,2,3,4]}"#);
+    assert_eq!(serde_json::from_str::<Token>(&json).unwrap(), t);
+
+    let verbose = format!(
+        r#"{{"pos":{},"loc":{}}}"#,
+        serde_json::to_string(&t.pos).unwrap(),
+        serde_json::to_string(&t.loc).unwrap()
+    );
+    assert_eq!(serde_json::from_str::<Token>(&verbose).unwrap(), t);
+
+    let mixed = r#"{"pos":{"line":1,"column":2},"loc":{"from":[1,2],"to":{"line":3,"column":4}}}"#;
+    assert_eq!(serde_json::from_str::<Token>(mixed).unwrap(), t);
+    assert!(serde_json::from_str::<Token>(r#"{"pos":[1],"loc":[1,2,3,4]}"#).is_err());
+}
+
+#[test]
+fn test_map() {
+    assert_eq!(pos(1, 2).map_line(|l| l + 1), pos(2, 2));
+    assert_eq!(pos(1, 2).map_column(|c| c * 2), pos(1, 4));
+    let l = locof!(1, 2, 3, 4).map(|p| p.map_line(|l| l + 10));
+    assert_eq!(l, locof!(11, 2, 13, 4));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_aliases() {
+    let l = locof!(1, 2, 3, 4);
+    for json in [
+        r#"{"from":{"line":1,"column":2},"to":{"line":3,"column":4}}"#,
+        r#"{"from":{"row":1,"col":2},"to":{"row":3,"col":4}}"#,
+        r#"{"start":{"line":1,"col":2},"end":{"row":3,"column":4}}"#,
+    ] {
+        assert_eq!(serde_json::from_str::<Loc>(json).unwrap(), l);
+    }
+    assert_eq!(
+        serde_json::to_string(&l).unwrap(),
+        r#"{"from":{"line":1,"column":2},"to":{"line":3,"column":4}}"#
+    );
+
+    #[derive(serde::Deserialize)]
+    struct Compact(#[serde(with = "crate::serde_compact")] Loc);
+    let json = r#"{"start":{"row":1,"col":2},"end":[3,4]}"#;
+    assert_eq!(serde_json::from_str::<Compact>(json).unwrap().0, l);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_code_host_fragments() {
+    let multi = locof!(11, 4, 13, 9);
+    let single = locof!(11, 4, 11, 9);
+    assert_eq!(multi.to_github_fragment(), "#L12-L14");
+    assert_eq!(single.to_github_fragment(), "#L12");
+    assert_eq!(multi.to_github_fragment_with_columns(), "#L12C5-L14C9");
+    assert_eq!(
+        single.start_loc().to_github_fragment_with_columns(),
+        "#L12C5"
+    );
+    assert_eq!(multi.to_gitlab_fragment(), "#L12-14");
+    assert_eq!(single.to_gitlab_fragment(), "#L12");
+    assert_eq!(locof!(11, 4, 13, 0).to_gitlab_fragment(), "#L12-13");
+    assert_eq!(
+        locof!(11, 4, 13, 0).to_github_fragment_with_columns(),
+        "#L12-L13"
+    );
+    assert_eq!(Loc::DUMMY.to_gitlab_fragment(), "");
+}
+
+#[test]
+fn test_dummy() {
+    assert!(Pos::DUMMY.is_dummy());
+    assert!(Loc::DUMMY.is_dummy());
+    assert!(!Loc::zero().is_dummy());
+    assert!(!locof!(0, 0, usize::MAX, usize::MAX).is_dummy());
+    assert_eq!(Spanned::dummy(1).loc, Loc::DUMMY);
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[test]
+fn test_spanned_serde_forms() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+    struct Ident {
+        name: String,
+    }
+
+    let ident = Spanned::new(
+        Ident {
+            name: "a".to_string(),
+        },
+        locof!(1, 2, 1, 3),
+    );
+
+    let json = serde_json::to_string(&ident).unwrap();
+    assert_eq!(
+        json,
+        r#"{"value":{"name":"a"},"loc":{"from":{"line":1,"column":2},"to":{"line":1,"column":3}}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<Spanned<Ident>>(&json).unwrap(),
+        ident
+    );
+
+    let nested = Spanned::new(Spanned::new(1, locof!(0, 1, 0, 2)), locof!(0, 0, 0, 3));
+    let json = serde_json::to_string(&nested).unwrap();
+    assert_eq!(
+        serde_json::from_str::<Spanned<Spanned<i32>>>(&json).unwrap(),
+        nested
+    );
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Flat(#[serde(with = "crate::spanned_flat")] Spanned<Ident>);
+    let json = serde_json::to_string(&Flat(ident.clone())).unwrap();
+    assert_eq!(
+        json,
+        r#"{"name":"a","loc":{"from":{"line":1,"column":2},"to":{"line":1,"column":3}}}"#
+    );
+    assert_eq!(serde_json::from_str::<Flat>(&json).unwrap().0, ident);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ValueOnly(#[serde(with = "crate::spanned_value_only")] Spanned<Spanned<i32>>);
+    let json = serde_json::to_string(&ValueOnly(nested)).unwrap();
+    assert_eq!(
+        json,
+        r#"{"value":1,"loc":{"from":{"line":0,"column":1},"to":{"line":0,"column":2}}}"#
+    );
+    let back = serde_json::from_str::<ValueOnly>(&json).unwrap().0;
+    assert_eq!(back.loc, Loc::DUMMY);
+    assert_eq!(back.value, nested.value);
+
+    use crate::spanned_value_only::ValueOnly as Wrapper;
+    let items: Vec<Wrapper<&str>> = vec![
+        spanned!("a", locof!(0, 0, 0, 1)).into(),
+        spanned!("b", locof!(0, 2, 0, 3)).into(),
+    ];
+    let json = serde_json::to_string(&items).unwrap();
+    assert_eq!(json, r#"["a","b"]"#);
+    let back: Vec<Spanned<&str>> = serde_json::from_str::<Vec<Wrapper<&str>>>(&json)
+        .unwrap()
+        .into_iter()
+        .map(Spanned::from)
+        .collect();
+    assert_eq!(back, vec![Spanned::dummy("a"), Spanned::dummy("b")]);
+    assert_eq!(
+        Spanned::<&str>::default(),
+        Spanned {
+            value: "",
+            loc: Loc::DUMMY
+        }
+    );
+    assert!(Spanned::<i32>::default().loc.is_dummy());
+    assert!(!Loc::default().is_dummy());
+    assert_eq!(Wrapper::<i32>::default().0, Spanned::dummy(0));
+}
+
+#[test]
+fn test_clamp_to() {
+    let bounds = locof!(1, 2, 3, 4);
+    assert_eq!(pos(0, 9).clamp_to(bounds), pos(1, 2));
+    assert_eq!(pos(1, 1).clamp_to(bounds), pos(1, 2));
+    assert_eq!(pos(2, 0).clamp_to(bounds), pos(2, 0));
+    assert_eq!(pos(3, 5).clamp_to(bounds), pos(3, 4));
+    const P: Pos = Pos::new(9, 9).clamp_to(Loc::new_at(3, 4, 1, 2));
+    assert_eq!(P, pos(3, 4));
+    assert_eq!(locof!(3, 4, 1, 2).normalize(), bounds);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_any() {
+    #[derive(Debug, serde::Deserialize)]
+    struct Any(#[serde(with = "crate::serde_any")] Loc);
+
+    let l = locof!(1, 2, 3, 4);
+    for json in [
+        "[1,2,3,4]",
+        "[[1,2],[3,4]]",
+        r#"{"from":[1,2],"to":[3,4]}"#,
+        r#"{"from":{"line":1,"column":2},"to":{"line":3,"column":4}}"#,
+    ] {
+        assert_eq!(serde_json::from_str::<Any>(json).unwrap().0, l, "{}", json);
+    }
+    for json in [
+        "[1,2,3]",
+        "[[1,2],[3,4],[5,6]]",
+        "[[1,2],3]",
+        "\"1:2\"",
+        r#"{"from":[1,2]}"#,
+    ] {
+        assert!(serde_json::from_str::<Any>(json).is_err(), "{}", json);
+    }
+    let err = serde_json::from_str::<Any>("\"1:2\"")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("or a Loc struct"), "{}", err);
+}
+
+#[cfg(feature = "codespan")]
+#[test]
+fn test_codespan() {
+    use core::convert::TryFrom;
+
+    let src = "fn main() {\n    let \u{e9} = 1;\n}";
+    let mut files = codespan::Files::new();
+    let id = files.add("main.rs", src);
+    let index = LineIndex::new(src);
+    for offset in [0, 5, 16, 22, 29, src.len()] {
+        let location = files.location(id, offset as u32).unwrap();
+        let p = index.pos_of(offset).unwrap();
+        assert_eq!(Pos::from(location), p);
+        assert_eq!(codespan::Location::try_from(p).unwrap(), location);
+    }
+    assert_eq!(
+        Pos::from(files.location(id, src.len() as u32).unwrap()),
+        pos(2, 1)
+    );
+    assert!(codespan::Location::try_from(pos(usize::MAX, 0)).is_err());
+
+    let span = codespan::Span::new(16, src.len() as u32);
+    let l = Loc::from_codespan_span(span, &index).unwrap();
+    assert_eq!(l, locof!(1, 4, 2, 1));
+    assert_eq!(l.to_codespan_span(&index), Some(span));
+    assert_eq!(
+        Loc::from_codespan_span(codespan::Span::new(0, 100), &index),
+        None
+    );
+}
+
+#[test]
+fn test_loc_bit_ops() {
+    let a = locof!(0, 0, 0, 5);
+    let b = locof!(0, 3, 1, 0);
+    let c = locof!(2, 0, 2, 4);
+    assert_eq!(a | b, a.merge(&b));
+    assert_eq!(a | b | c, locof!(0, 0, 2, 4));
+    assert_eq!(a & b, locof!(0, 3, 0, 5));
+    assert_eq!(a & c, locof!(0, 5, 0, 5));
+    assert_eq!(c & a, locof!(0, 5, 0, 5));
+}
+
+#[test]
+fn test_loc_macro_here() {
+    let line = line!() as usize;
+    let here = loc!();
+    assert_eq!(here, Loc::from(pos(line + 1, 16)));
+    let value = loc!(vec![1, 2]);
+    assert_eq!(value.value, [1, 2]);
+    assert_eq!(value.loc, locof!(line + 3, 17, line + 3, 33));
+    assert_eq!(__char_count("\u{e9}\u{4e2d}a"), 3);
+}
+
+#[test]
+fn test_pos_with() {
+    const P: Pos = Pos::new(1, 2).with_line(5);
+    assert_eq!(P, pos(5, 2));
+    assert_eq!(P.with_column(7), pos(5, 7));
+}
+
+#[test]
+fn test_spanned_macro() {
+    const S: Spanned<&str> = spanned!("a", Loc::new_at(1, 2, 3, 4));
+    assert_eq!(S.loc, locof!(1, 2, 3, 4));
+    let mut calls = 0;
+    let line = line!() as usize;
+    let s = spanned!({
+        calls += 1;
+        calls
+    });
+    assert_eq!(s, Spanned::new(1, Loc::from(pos(line + 1, 13))));
+    assert_eq!(calls, 1);
+}
+
+#[test]
+fn test_display_smart() {
+    assert_eq!(locof!(1, 2, 1, 2).display_smart().to_string(), "1:2");
+    assert_eq!(locof!(1, 2, 1, 6).display_smart().to_string(), "1:2-6");
+    assert_eq!(locof!(1, 2, 3, 4).display_smart().to_string(), "1:2-3:4");
+    assert_eq!(locof!(1, 2, 3, 4).to_string(), "at 1:2 to 3:4");
+}
+
+#[test]
+fn test_here_macro() {
+    let line = line!() as usize;
+    let here = here!();
+    assert_eq!(here.file, file!());
+    assert_eq!(here.pos, pos(line + 1, 16));
+    assert_eq!(here.to_string(), format!("{}:{}:16", file!(), line + 1));
+    assert_eq!(Pos::from(here), here.pos);
+}
+
+#[test]
+fn test_based() {
+    use core::mem::size_of;
+
+    assert_eq!(size_of::<OneBased<Loc>>(), size_of::<Loc>());
+    const ONE: OneBased<Pos> = ZeroBased(Pos::zero()).to_one_based();
+    assert_eq!(ONE, OneBased(pos(1, 1)));
+    assert_eq!(ONE.to_zero_based(), Some(ZeroBased(pos(0, 0))));
+    assert_eq!(OneBased(pos(0, 1)).to_zero_based(), None);
+    let l = ZeroBased(locof!(0, 1, 2, 3));
+    let one: OneBased<Loc> = l.into();
+    assert_eq!(one, OneBased(locof!(1, 2, 3, 4)));
+    assert_eq!(one.to_zero_based(), Some(l));
+}
+
+#[test]
+fn test_const_macros() {
+    const P: Pos = posof!(1, 2);
+    const Q: Pos = posof!([1, 2]);
+    static L: Loc = locof!(1, 2, 3, 4);
+    const M: Loc = locof!([1, 2], [3, 4]);
+    const N: Loc = locof!((1, 2), (3, 4));
+    const O: Loc = loc(pos(1, 2), pos(3, 4));
+    assert_eq!(P, Q);
+    assert_eq!(L, M);
+    assert_eq!(M, N);
+    assert_eq!(N, O);
+    assert_eq!(locof!((1, 2), [3, 4]), L);
+}
+
+#[test]
+fn test_loc_lines() {
+    let l = locof!(1, 2, 4, 3);
+    assert!(!l.touches_line(0));
+    assert!(l.touches_line(1) && l.touches_line(4));
+    assert!(!l.touches_line(5));
+    assert!(!l.fully_covers_line(1));
+    assert!(l.fully_covers_line(2) && l.fully_covers_line(3));
+    assert!(!l.fully_covers_line(4));
+    assert!(locof!(1, 0, 2, 0).fully_covers_line(1));
+}
+
+#[test]
+fn test_pos_from_offset() {
+    let src = "ab\nc\u{e9}d\n\u{4e2d}";
+    assert_eq!(Pos::from_offset(src, 0), Some(pos(0, 0)));
+    assert_eq!(Pos::from_offset(src, 3), Some(pos(1, 0)));
+    assert_eq!(Pos::from_offset(src, 5), None);
+    assert_eq!(Pos::from_offset(src, 6), Some(pos(1, 2)));
+    assert_eq!(Pos::from_offset(src, src.len()), Some(pos(2, 1)));
+    assert_eq!(Pos::from_offset(src, src.len() + 1), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_merge_overlapping() {
+    let merged = Loc::merge_overlapping(vec![
+        locof!(3, 0, 3, 5),
+        locof!(0, 0, 0, 4),
+        locof!(0, 2, 1, 0),
+        locof!(1, 0, 1, 1),
+        locof!(3, 6, 3, 2),
+    ]);
+    assert_eq!(merged, [locof!(0, 0, 1, 1), locof!(3, 0, 3, 6)]);
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!("3:7".parse::<Pos>(), Ok(pos(3, 7)));
+    assert_eq!("3:7-3:12".parse::<Loc>(), Ok(locof!(3, 7, 3, 12)));
+    assert_eq!("3:7-12".parse::<Loc>(), Ok(locof!(3, 7, 3, 12)));
+    assert_eq!("3:7".parse::<Loc>(), Ok(locof!(3, 7, 3, 7)));
+    assert_eq!("3:7-4:1".parse::<Loc>(), Ok(locof!(3, 7, 4, 1)));
+    for bad in [
+        "", "3", "3:", ":7", "3:7 ", "3:7-", "3:7-4:", "3:7+4:1", "a:b",
+    ] {
+        assert!(bad.parse::<Loc>().is_err(), "{}", bad);
+    }
+    assert!("3:7-1".parse::<Pos>().is_err());
+    assert_eq!(
+        "99999999999999999999999:1"
+            .parse::<Pos>()
+            .unwrap_err()
+            .message(),
+        "number too large"
+    );
+    const P: Pos = pos_str!("3:7");
+    const L: Loc = loc_str!("3:7-3:12");
+    assert_eq!(P, pos(3, 7));
+    assert_eq!(L.from, P);
+    assert_eq!(loc_str!("1:2-3:4"), locof!(1, 2, 3, 4));
+}
+
+mod macros_only {
+    use crate::{here, loc, loc_lit, loc_str, locof, pos, pos_lit, pos_str, posof, spanned};
+
+    #[test]
+    fn test_macro_hygiene() {
+        let a = locof!(1, 2, 3, 4);
+        assert_eq!(a, locof!([1, 2], [3, 4]));
+        assert_eq!(a, locof!((1, 2)..(3, 4)));
+        assert_eq!(a, loc_str!("1:2-3:4"));
+        assert_eq!(posof!(1, 2), pos_str!("1:2"));
+        assert_eq!(a, loc_lit!("1:2-3:4"));
+        assert_eq!(posof!(1, 2), pos_lit!("1:2"));
+        assert_eq!(posof!([1, 2]), a.from);
+        assert_eq!(pos!().line, line!() as usize);
+        assert_eq!(loc!().from.line, line!() as usize);
+        assert_eq!(spanned!(1, a).loc, a);
+        assert_eq!(here!().file, file!());
+    }
+
+    #[test]
+    fn test_single_evaluation() {
+        let mut n = 0;
+        let mut next = || {
+            n += 1;
+            n
+        };
+        let l = locof!(next(), next(), next(), next());
+        assert_eq!(l, locof!(1, 2, 3, 4));
+        let mut m = 0;
+        let mut p = || {
+            m += 1;
+            posof!(m, m)
+        };
+        let l = locof!(p(), p());
+        assert_eq!(l, locof!(1, 1, 2, 2));
+    }
+}
+
+#[test]
+fn test_into_parts() {
+    let (from, to) = locof!(1, 2, 3, 4).into_parts();
+    assert_eq!((from, to), (pos(1, 2), pos(3, 4)));
+    let (line, column) = from.into_parts();
+    assert_eq!((line, column), (1, 2));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_span_sweep() {
+    let spans = [
+        locof!(0, 0, 2, 0),
+        locof!(1, 5, 1, 9),
+        locof!(1, 9, 3, 1),
+        locof!(5, 0, 5, 0),
+        locof!(4, 3, 4, 0),
+    ];
+    let mut sweep = SpanSweep::new(&spans);
+    let mut got = Vec::new();
+    while let Some((loc, active)) = sweep.next_interval() {
+        got.push((loc, active.to_vec()));
+    }
+    assert_eq!(
+        got,
+        [
+            (locof!(0, 0, 1, 5), vec![0]),
+            (locof!(1, 5, 1, 9), vec![0, 1]),
+            (locof!(1, 9, 2, 0), vec![0, 2]),
+            (locof!(2, 0, 3, 1), vec![2]),
+            (locof!(4, 0, 4, 3), vec![4]),
+        ]
+    );
+    assert_eq!(SpanSweep::new(&[]).next_interval(), None);
+}
+
+#[test]
+fn test_assert_macros() {
+    use std::panic::catch_unwind;
+
+    fn message(r: std::thread::Result<()>) -> String {
+        let e = r.unwrap_err();
+        e.downcast_ref::<String>().cloned().unwrap()
+    }
+
+    assert_loc_eq!(locof!(1, 2, 3, 4), 1, 2, 3, 4);
+    assert_pos_eq!(pos(1, 2), (1, 2));
+
+    let msg = message(catch_unwind(|| {
+        assert_loc_eq!(locof!(1, 2, 3, 4), 1, 2, 3, 5)
+    }));
+    assert_eq!(
+        msg,
+        "assertion `left == right` failed\n   left: 1:2-3:4\n  right: 1:2-3:5\ndiffers: to.column (4 != 5)"
+    );
+    let msg = message(catch_unwind(|| {
+        assert_loc_eq!(locof!(1, 2, 3, 4), locof!(0, 2, 3, 5), "token {}", 7)
+    }));
+    assert!(
+        msg.starts_with("assertion `left == right` failed: token 7\n"),
+        "{}",
+        msg
+    );
+    assert!(
+        msg.ends_with("differs: from.line (1 != 0), to.column (4 != 5)"),
+        "{}",
+        msg
+    );
+    let msg = message(catch_unwind(|| assert_pos_eq!(pos(1, 2), pos(1, 3))));
+    assert!(
+        msg.contains("left: 1:2\n  right: 1:3\ndiffers: column (2 != 3)"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn test_column_ops() {
+    let mut cursor = pos(1, 2);
+    cursor += 3;
+    assert_eq!(cursor, pos(1, 5));
+    assert_eq!(cursor - 2, pos(1, 3));
+    cursor -= 10;
+    assert_eq!(cursor, pos(1, 0));
+    assert_eq!(pos(0, usize::MAX) + 1, pos(0, usize::MAX));
+
+    let mut l = locof!(1, 2, 3, 4);
+    l += 2;
+    assert_eq!(l, locof!(1, 4, 3, 6));
+    l -= 5;
+    assert_eq!(l, locof!(1, 0, 3, 1));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_dbg_loc() {
+    let s = String::from("owned");
+    let moved: String = dbg_loc!(s);
+    assert_eq!(moved, "owned");
+    let r: &String = dbg_loc!(&moved, locof!(0, 0, 0, 5));
+    assert_eq!(r, "owned");
+
+    let mut calls = 0;
+    let v = dbg_loc!({
+        calls += 1;
+        calls
+    }) + 1;
+    assert_eq!((v, calls), (2, 1));
+}
+
+#[test]
+fn test_relative_within() {
+    let outer = locof!(2, 4, 5, 0);
+    let inner = locof!(2, 6, 3, 1);
+    let rel = inner.relative_within(&outer).unwrap();
+    assert_eq!(rel, locof!(0, 2, 1, 1));
+    assert_eq!(rel.rebase(outer.from), inner);
+    assert_eq!(outer.relative_within(&outer), Some(locof!(0, 0, 3, 0)));
+    assert_eq!(locof!(1, 0, 2, 5).relative_within(&outer), None);
+    assert_eq!(locof!(4, 0, 5, 1).relative_within(&outer), None);
+}
+
+#[test]
+fn test_pos0_macro() {
+    let line = line!() as usize;
+    let p = pos0!();
+    assert_eq!(p, pos(line, 12));
+    let p = pos!(2, -3);
+    assert_eq!(p, pos(line + 5, 10));
+    let p = pos!(-100_000, -100);
+    assert_eq!(p, pos(0, 0));
+}
+
+#[test]
+fn test_display_gnu() {
+    assert_eq!(pos(11, 4).display_gnu("foo.c").to_string(), "foo.c:12:5");
+    assert_eq!(
+        locof!(11, 4, 11, 4).display_gnu("foo.c").to_string(),
+        "foo.c:12:5"
+    );
+    assert_eq!(
+        locof!(11, 4, 13, 8).display_gnu("foo.c").to_string(),
+        "foo.c:12.5-14.8"
+    );
+    assert_eq!(
+        locof!(0, 0, 0, 3).display_gnu("a.rs").to_string(),
+        "a.rs:1.1-1.3"
+    );
+    assert_eq!(
+        locof!(11, 4, 13, 0).display_gnu("foo.c").to_string(),
+        "foo.c:12.5-13"
+    );
+}
+
+#[test]
+#[cfg(feature = "ansi")]
+fn test_display_colored() {
+    let l = locof!(1, 2, 3, 4);
+    assert_eq!(
+        l.display_colored(false).to_string(),
+        l.display_smart().to_string()
+    );
+    assert_eq!(
+        locof!(1, 2, 1, 6).display_colored(false).to_string(),
+        "1:2-6"
+    );
+    assert_eq!(pos(1, 2).display_colored(false).to_string(), "1:2");
+    assert_eq!(
+        pos(1, 2).display_colored(true).to_string(),
+        "\x1b[1m1\x1b[0m\x1b[2m:\x1b[0m\x1b[1m2\x1b[0m"
+    );
+    assert!(!l.display_colored(false).to_string().contains('\x1b'));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_line_index_apply_edit() {
+    let edits: &[(&str, Range<usize>, &str)] = &[
+        ("ab\ncd\nef", 1..1, "x\ny"),
+        ("ab\ncd\nef", 1..7, ""),
+        ("ab\ncd\nef", 2..3, "é\n\n"),
+        ("aé\nü\n", 0..4, "z"),
+        ("aé\nü\n", 7..7, "ö"),
+        ("", 0..0, "\n"),
+        ("a\n", 0..2, "a\nb\nc"),
+    ];
+    for (text, range, new_text) in edits.iter().cloned() {
+        let mut index = LineIndex::new(text);
+        index.apply_edit(range.clone(), new_text);
+        let mut edited = String::from(text);
+        edited.replace_range(range, new_text);
+        assert_eq!(index, LineIndex::new(&edited), "{:?} -> {:?}", text, edited);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_line_index_apply_edits() {
+    let text = "ab\ncd\nef";
+    let mut index = LineIndex::new(text);
+    index.apply_edits(vec![
+        (8..8, "\n"),
+        (0..0, "\u{e9}\n"),
+        (2..5, ""),
+        (2..2, "x"),
+    ]);
+    assert_eq!(index, LineIndex::new("\u{e9}\nabx\nef\n"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+#[should_panic(expected = "overlapping edits")]
+fn test_line_index_apply_edits_overlap() {
+    LineIndex::new("abcdef").apply_edits(vec![(0..3, ""), (2..4, "x")]);
+}
+
+#[test]
+fn test_display_rustc() {
+    assert_eq!(
+        locof!(3, 8, 3, 12).display_rustc("src/main.rs").to_string(),
+        " --> src/main.rs:4:9"
+    );
+    assert_eq!(
+        locof!(7, 0, 9, 1).display_rustc("src/main.rs").to_string(),
+        "  --> src/main.rs:8:1"
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_snippet_rustc() {
+    let source = SourceText::new(
+        "fn main() {\n    let x: i32 = \"a\";\n    bar(foo(\n        1,\n    ));\n}\n",
+    );
+    let one = Snippet::new(&source, locof!(1, 17, 1, 20))
+        .unwrap()
+        .with_label("expected `i32`, found `&str`")
+        .with_rustc_header("src/main.rs");
+    assert_eq!(
+        format!("{}\n", one),
+        include_str!("../tests/golden/rustc_one_line.txt")
+    );
+    let three = Snippet::new(&source, locof!(2, 8, 4, 6))
+        .unwrap()
+        .with_label("bad call")
+        .with_rustc_header("src/main.rs");
+    assert_eq!(
+        format!("{}\n", three),
+        include_str!("../tests/golden/rustc_three_lines.txt")
+    );
+
+    let whole = Snippet::new(&source, locof!(0, 0, 6, 0)).unwrap();
+    assert_eq!(
+        whole.to_string(),
+        "1 | / fn main() {\n2 | |     let x: i32 = \"a\";\n3 | |     bar(foo(\n4 | |         1,\n5 | |     ));\n6 | | }\n  | |__^"
+    );
+    let tabs = SourceText::new("\tx = ab;");
+    assert_eq!(
+        Snippet::new(&tabs, locof!(0, 5, 0, 7)).unwrap().to_string(),
+        "1 |     x = ab;\n  |         ^^"
+    );
+    let lines: String = (0..12).map(|i| format!("line {}\n", i)).collect();
+    let wide = SourceText::new(&lines);
+    assert_eq!(
+        Snippet::new(&wide, locof!(8, 0, 9, 2))
+            .unwrap()
+            .with_rustc_header("a.rs")
+            .to_string(),
+        "  --> a.rs:9:1\n   |\n9  | / line 8\n10 | | line 9\n   | |__^"
+    );
+    assert_eq!(Snippet::new(&source, locof!(1, 2, 0, 0)), None);
+    assert_eq!(Snippet::new(&source, locof!(9, 0, 9, 1)), None);
+    assert_eq!(Snippet::new(&source, Loc::DUMMY), None);
+}
+
+#[test]
+fn test_accessors() {
+    let p = pos(3, 7);
+    assert_eq!((p.line(), p.column()), (p.line, p.column));
+    let l = locof!(1, 2, 3, 4);
+    assert_eq!(l.start(), l.from);
+    assert_eq!(l.end(), l.to);
+    assert_eq!(Loc::new(l.start(), l.end()), l);
+    assert_eq!(Loc::from(l.start()), locof!(1, 2, 1, 2));
+    assert_eq!(l.start_line(), 1);
+    assert_eq!(l.to_tuple(), (1, 2, 3, 4));
+    assert_eq!(Loc::from(l.to_tuple()), l);
+}
+
+#[cfg(feature = "legacy-conversions")]
+#[test]
+fn test_legacy_conversions() {
+    let p = pos(1, 2);
+    let l = locof!(1, 2, 3, 4);
+    let line: usize = p.into();
+    assert_eq!(line, p.line());
+    let [line]: [usize; 1] = p.into();
+    assert_eq!(line, p.line());
+    let line: usize = l.into();
+    assert_eq!(line, l.start_line());
+    let [line]: [usize; 1] = l.into();
+    assert_eq!(line, l.start_line());
+    let from: [usize; 2] = l.into();
+    assert_eq!(from, [1, 2]);
+}
+
+#[test]
+fn test_display_in_file() {
+    let l = locof!(12, 5, 14, 9);
+    assert_eq!(
+        l.in_file("src/main.rs").to_string(),
+        "src/main.rs:12:5-14:9"
+    );
+    assert_eq!(
+        format!("{:>6}|", locof!(0, 1, 0, 2).in_file("a")),
+        "a:0:1-0:2|"
+    );
+    assert_eq!(
+        format!("{:>12}|", locof!(0, 1, 0, 2).in_file("a")),
+        "   a:0:1-0:2|"
+    );
+    let long = "dir/".repeat(30) + "main.rs";
+    let shown = format!("{}:0:1-0:2", long);
+    assert_eq!(
+        format!("{:>140}|", locof!(0, 1, 0, 2).in_file(&long)),
+        format!("{}{}|", " ".repeat(140 - shown.len()), shown)
+    );
+    assert_eq!(
+        format!("{:-^16}", locof!(0, 1, 0, 1).in_file("\u{e9}t\u{e9}.rs")),
+        "-\u{e9}t\u{e9}.rs:0:1-0:1-"
+    );
+    assert_eq!(
+        format!("{:.9}", locof!(0, 1, 0, 2).in_file(&long)),
+        "dir/dir/d"
+    );
+    assert_eq!(
+        Loc::DUMMY.in_file("src/main.rs").to_string(),
+        "src/main.rs:<unknown>"
+    );
+}
+
+#[test]
+fn test_display_bare() {
+    let p = pos(1, 2);
+    let l = locof!(1, 2, 3, 4);
+    assert_eq!(p.bare().to_string(), "1:2");
+    assert_eq!(l.bare().to_string(), "1:2-3:4");
+    assert_eq!(format!("error: {} is bad", p.bare()), "error: 1:2 is bad");
+    assert_eq!(p.to_string(), format!("at {}", p.bare()));
+    assert_eq!(l.to_string(), "at 1:2 to 3:4");
+}
+
+#[cfg(feature = "annotate-snippets")]
+#[test]
+fn test_annotate_snippets() {
+    use annotate_snippets::display_list::DisplayList;
+    use annotate_snippets::snippet::{AnnotationType, Slice, Snippet};
+
+    let source = "let é = 1;\nlet b = é;";
+    let index = LineIndex::new(source);
+    let loc = locof!(1, 8, 1, 9);
+    assert_eq!(index.char_offset_of(loc.from), Some(19));
+    assert_eq!(loc.to_annotation_range(&index), Some((19, 20)));
+    assert_eq!(locof!(5, 0, 5, 1).to_annotation_range(&index), None);
+
+    let snippet = Snippet {
+        title: None,
+        footer: vec![],
+        slices: vec![Slice {
+            source,
+            line_start: 1,
+            origin: None,
+            fold: false,
+            annotations: vec![loc
+                .to_source_annotation(&index, "here", AnnotationType::Error)
+                .unwrap()],
+        }],
+        opt: Default::default(),
+    };
+    let out = DisplayList::from(snippet).to_string();
+    assert!(
+        out.contains("2 | let b = é;\n  |         ^ here"),
+        "{}",
+        out
+    );
+}
+
+#[test]
+fn test_display_dummy() {
+    let p = Pos::DUMMY;
+    let l = Loc::DUMMY;
+    let unknown = "<unknown>";
+    assert_eq!(p.to_string(), unknown);
+    assert_eq!(l.to_string(), unknown);
+    assert_eq!(p.bare().to_string(), unknown);
+    assert_eq!(l.bare().to_string(), unknown);
+    assert_eq!(p.display_one_based().to_string(), unknown);
+    assert_eq!(l.display_one_based().to_string(), unknown);
+    assert_eq!(l.display_smart().to_string(), unknown);
+    assert_eq!(Spanned::dummy("fn").to_string(), "fn (<unknown>)");
+    #[cfg(feature = "ansi")]
+    assert_eq!(l.display_colored(true).to_string(), unknown);
+    #[cfg(feature = "compact-debug")]
+    assert_eq!(format!("{:?}", p), "Pos(<unknown>)");
+
+    let in_file = "a.rs:<unknown>";
+    assert_eq!(p.display_gnu("a.rs").to_string(), in_file);
+    assert_eq!(l.display_gnu("a.rs").to_string(), in_file);
+    assert_eq!(l.in_file("a.rs").to_string(), in_file);
+    assert_eq!(SourceLocation::new("a.rs", p).to_string(), in_file);
+    assert_eq!(l.display_rustc("a.rs").to_string(), " --> a.rs:<unknown>");
+
+    let open = Loc::from(pos(1, 2)..);
+    assert_eq!(open.bare().to_string(), "1:2-<unknown>");
+    assert_eq!(open.display_smart().to_string(), "1:2-<unknown>");
+    assert_eq!(open.display_gnu("a.rs").to_string(), "a.rs:2:3");
+    assert_eq!(open.display_rustc("a.rs").to_string(), " --> a.rs:2:3");
+    assert_eq!(pos(0, 0).to_string(), "at 0:0");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_one_based_saturates() {
+    let max = usize::MAX;
+    let p = pos(max, 0);
+    let l = Loc::new(p, pos(max, 3));
+    assert_eq!(ZeroBased(p).to_one_based(), OneBased(pos(max, 1)),);
+    assert_eq!(p.display_gnu("a.rs").to_string(), format!("a.rs:{}:1", max));
+    assert_eq!(
+        l.display_gnu("a.rs").to_string(),
+        format!("a.rs:{0}.1-{0}.3", max)
+    );
+    assert!(l
+        .display_rustc("a.rs")
+        .to_string()
+        .ends_with(&format!("--> a.rs:{}:1", max)));
+    assert_eq!(l.to_github_fragment(), format!("#L{}", max));
+    assert_eq!(
+        l.github_fragment(true).to_string(),
+        format!("#L{0}C1-L{0}C3", max)
+    );
+    assert_eq!(
+        annotation_command(AnnotationLevel::Error, "a.rs", l, "bad"),
+        format!("::error file=a.rs,line={},col=1,endColumn=3::bad", max)
+    );
+    #[cfg(feature = "serde")]
+    {
+        let region = l.to_sarif_region().unwrap();
+        assert_eq!((region.start_line, region.end_line), (max, max));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_eof() {
+    let text = "fn é() {\n";
+    let index = LineIndex::new(text);
+    assert_eq!(index.end_pos(), pos(1, 0));
+    let eof = Loc::new_same_pos(index.end_pos());
+    assert!(eof.is_eof_in(&index));
+    assert!(!locof!(0, 8, 0, 8).is_eof_in(&index));
+    assert!(!locof!(0, 0, 1, 0).is_eof_in(&index));
+    assert_eq!(
+        eof.eof_snippet_in(text, &index).as_deref(),
+        Some("fn é() {\n        ^")
+    );
+
+    let text = "a\nbé";
+    let index = LineIndex::new(text);
+    assert_eq!(index.end_pos(), pos(1, 2));
+    let eof = locof!(1, 2, 1, 2);
+    assert_eq!(eof.eof_snippet_in(text, &index).as_deref(), Some("bé\n  ^"));
+    assert_eq!(locof!(1, 1, 1, 1).eof_snippet_in(text, &index), None);
+    assert!(locof!(0, 0, 0, 0).is_eof_in(&LineIndex::new("")));
+}
+
+#[test]
+fn test_spanned_fmt() {
+    let s = spanned!("fn", locof!(1, 2, 3, 4));
+    assert_eq!(format!("{:?}", s), r#"Spanned(1:2-3:4, "fn")"#);
+    assert_eq!(
+        format!("{:#?}", s),
+        "Spanned(\n    1:2-3:4,\n    \"fn\",\n)"
+    );
+    assert_eq!(s.to_string(), "fn (at 1:2-3:4)");
+    assert_eq!(Spanned::dummy("fn").to_string(), "fn (<unknown>)");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_underline_in() {
+    let line = "\t\tlet x = 1;";
+    assert_eq!(
+        locof!(3, 6, 3, 7).underline_in(line, 3).as_deref(),
+        Some("\t\t    ^")
+    );
+    assert_eq!(
+        locof!(0, 0, 0, 3)
+            .underline_in_with("let x", 0, '~')
+            .as_deref(),
+        Some("~~~")
+    );
+    assert_eq!(
+        locof!(0, 2, 0, 2).underline_in("abc", 0).as_deref(),
+        Some("  ^")
+    );
+    assert_eq!(
+        locof!(0, 4, 2, 1).underline_in("abcdef", 0).as_deref(),
+        Some("    ^^")
+    );
+    assert_eq!(
+        locof!(0, 4, 2, 1).underline_in("abc", 1).as_deref(),
+        Some("^^^")
+    );
+    assert_eq!(
+        locof!(0, 4, 2, 1).underline_in("abc", 2).as_deref(),
+        Some("^")
+    );
+    assert_eq!(locof!(0, 4, 2, 1).underline_in("abc", 3), None);
+    assert_eq!(locof!(0, 0, 0, 9).underline_in("abc", 0), None);
+}
+
+#[test]
+fn test_snap_to_words() {
+    let src = "let foo_bar = baz(1);\n  qux";
+    assert_eq!(locof!(0, 6, 0, 7).snap_to_words(src), locof!(0, 4, 0, 11));
+    assert_eq!(locof!(0, 5, 0, 5).snap_to_words(src), locof!(0, 4, 0, 11));
+    assert_eq!(locof!(0, 3, 0, 3).snap_to_words(src), locof!(0, 3, 0, 3));
+    assert_eq!(
+        locof!(0, 11, 0, 16).snap_to_words(src),
+        locof!(0, 11, 0, 17)
+    );
+    assert_eq!(locof!(0, 19, 1, 3).snap_to_words(src), locof!(0, 19, 1, 5));
+    assert_eq!(locof!(5, 0, 5, 1).snap_to_words(src), locof!(5, 0, 5, 1));
+}
+
+#[cfg(feature = "unicode-width")]
+#[test]
+fn test_visual_column() {
+    assert_eq!(visual_column("abc", 2, 4), 2);
+    assert_eq!(visual_column("日本語x", 3, 4), 6);
+    assert_eq!(visual_column("🦀 x", 2, 4), 3);
+    assert_eq!(visual_column("e\u{301}x", 2, 4), 1);
+    assert_eq!(visual_column("\tx", 1, 4), 4);
+    assert_eq!(visual_column("ab\t日x", 4, 4), 6);
+}
+
+#[cfg(all(feature = "unicode-width", feature = "alloc"))]
+#[test]
+fn test_underline_visual_width() {
+    assert_eq!(
+        locof!(0, 3, 0, 4).underline_in("日本語x", 0).as_deref(),
+        Some("      ^")
+    );
+    assert_eq!(
+        locof!(0, 1, 0, 3).underline_in("x日本語", 0).as_deref(),
+        Some(" ^^^^")
+    );
+    assert_eq!(
+        locof!(0, 2, 0, 3).underline_in("e\u{301}x", 0).as_deref(),
+        Some(" ^")
+    );
+    assert_eq!(
+        locof!(0, 2, 0, 3).underline_in("\t🦀x", 0).as_deref(),
+        Some("\t  ^")
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_nearest_pos() {
+    // `€` is 3 bytes at 1..4, `é` 2 bytes at 5..7
+    let index = LineIndex::new("a€\né");
+    assert_eq!(index.pos_of(2), None);
+    assert_eq!(index.nearest_pos(0), pos(0, 0));
+    assert_eq!(index.nearest_pos(2), pos(0, 1));
+    assert_eq!(index.nearest_pos(3), pos(0, 2));
+    assert_eq!(index.nearest_pos(6), pos(1, 0));
+    assert_eq!(index.nearest_pos(7), pos(1, 1));
+    assert_eq!(index.nearest_pos(100), pos(1, 1));
+}
+
+#[test]
+fn test_display_one_based() {
+    let p = pos(0, 0);
+    assert_eq!(p.display_one_based().to_string(), "1:1");
+    assert_eq!(p.bare().to_string(), "0:0");
+    assert_eq!(p.to_string(), "at 0:0");
+    assert_eq!(p, pos(0, 0));
+    assert_eq!(
+        locof!(0, 0, 0, 2).display_one_based().to_string(),
+        "1:1-1:3"
+    );
+    assert_eq!(Pos::DUMMY.display_one_based().to_string(), "<unknown>");
+    assert_eq!(
+        Loc::from(pos(0, 1)..).display_one_based().to_string(),
+        "1:2-<unknown>"
+    );
+}
+
+#[cfg(feature = "compact-debug")]
+#[test]
+fn test_compact_debug() {
+    let v = vec![locof!(1, 2, 3, 4), locof!(5, 6, 5, 8)];
+    assert_eq!(format!("{:?}", v), "[Loc(1:2..3:4), Loc(5:6..5:8)]");
+    assert_eq!(format!("{:?}", [pos(1, 2)]), "[Pos(1:2)]");
+    assert_eq!(
+        format!("{:#?}", pos(1, 2)),
+        "Pos {\n    line: 1,\n    column: 2,\n}"
+    );
+    assert_eq!(
+        format!("{:#?}", vec![locof!(1, 2, 3, 4)]),
+        "[
+    Loc {
+        from: Pos {
+            line: 1,
+            column: 2,
+        },
+        to: Pos {
+            line: 3,
+            column: 4,
+        },
+    },
+]"
+    );
+}
+
+#[test]
+fn test_display_padding() {
+    assert_eq!(format!("{:>10}", pos(1, 2)), "    at 1:2");
+    assert_eq!(format!("[{:>8}]", pos(1, 2).bare()), "[     1:2]");
+    assert_eq!(format!("[{:<8}]", pos(1, 2).bare()), "[1:2     ]");
+    assert_eq!(
+        format!("[{:*^9}]", locof!(1, 2, 3, 4).bare()),
+        "[*1:2-3:4*]"
+    );
+    assert_eq!(
+        format!("[{:>9}]", locof!(1, 2, 1, 6).display_smart()),
+        "[    1:2-6]"
+    );
+    assert_eq!(format!("[{:>12}]", Pos::DUMMY), "[   <unknown>]");
+    assert_eq!(format!("{}", locof!(1, 2, 3, 4)), "at 1:2 to 3:4");
+    assert_eq!(format!("{:.4}", locof!(1, 2, 3, 4)), "at 1");
+    let max = Loc::new_at(usize::MAX - 1, usize::MAX, usize::MAX, usize::MAX - 1);
+    assert_eq!(format!("{:>1}", max), max.to_string());
+}
+
+#[test]
+fn test_github_fragment() {
+    let point = locof!(9, 4, 9, 4);
+    let single = locof!(9, 4, 9, 9);
+    let multi = locof!(9, 4, 11, 3);
+    assert_eq!(point.github_fragment(false).to_string(), "#L10");
+    assert_eq!(single.github_fragment(false).to_string(), "#L10");
+    assert_eq!(multi.github_fragment(false).to_string(), "#L10-L12");
+    assert_eq!(point.github_fragment(true).to_string(), "#L10C5");
+    assert_eq!(single.github_fragment(true).to_string(), "#L10C5-L10C9");
+    assert_eq!(multi.github_fragment(true).to_string(), "#L10C5-L12C3");
+    assert_eq!(pos(9, 4).github_fragment().to_string(), "#L10C5");
+    let to_line_start = locof!(9, 4, 11, 0);
+    assert_eq!(to_line_start.github_fragment(false).to_string(), "#L10-L11");
+    assert_eq!(to_line_start.github_fragment(true).to_string(), "#L10-L11");
+    assert_eq!(
+        locof!(9, 4, 10, 0).github_fragment(true).to_string(),
+        "#L10"
+    );
+    assert_eq!(Loc::DUMMY.github_fragment(true).to_string(), "");
+    assert_eq!(Pos::DUMMY.github_fragment().to_string(), "");
+    let open = Loc::new(pos(9, 4), Pos::DUMMY);
+    assert_eq!(open.github_fragment(false).to_string(), "#L10");
+    assert_eq!(open.github_fragment(true).to_string(), "#L10C5");
+}
+
+#[cfg(feature = "text-size")]
+#[test]
+fn test_text_size() {
+    use text_size::{TextRange, TextSize};
+
+    let src = "fn main() {\n    let \u{e9} = 1;\n}";
+    let index = LineIndex::new(src);
+    assert_eq!(
+        Pos::from_text_size(TextSize::from(18), &index),
+        Some(pos(1, 6))
+    );
+    assert_eq!(pos(1, 9).to_text_size(&index), Some(TextSize::from(22)));
+    assert_eq!(Pos::from_text_size(TextSize::from(21), &index), None);
+
+    let range = TextRange::new(16.into(), 22.into());
+    let l = Loc::from_text_range(range, &index).unwrap();
+    assert_eq!(l, locof!(1, 4, 1, 9));
+    assert_eq!(l.to_text_range(&index), Some(range));
+    assert_eq!(locof!(5, 0, 5, 1).to_text_range(&index), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_sarif() {
+    let l = locof!(11, 4, 13, 8);
+    let region = l.to_sarif_region().unwrap();
+    assert_eq!(
+        serde_json::to_string(&region).unwrap(),
+        r#"{"startLine":12,"startColumn":5,"endLine":14,"endColumn":9}"#
+    );
+    let back: SarifRegion =
+        serde_json::from_str(r#"{"startLine":12,"startColumn":5,"endLine":14,"endColumn":9}"#)
+            .unwrap();
+    assert_eq!(Loc::from_sarif_region(&back), Some(l));
+    assert_eq!(
+        Loc::from_sarif_region(&Loc::zero().to_sarif_region().unwrap()),
+        Some(Loc::zero())
+    );
+    assert_eq!(
+        Loc::from_sarif_region(&SarifRegion {
+            start_line: 0,
+            start_column: 1,
+            end_line: 1,
+            end_column: 1
+        }),
+        None
+    );
+
+    let result = locof!(0, 0, 0, 3).to_sarif_result("src/main.rs", "E001", "bad");
+    assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        r#"{"ruleId":"E001","message":{"text":"bad"},"locations":[{"physicalLocation":{"artifactLocation":{"uri":"src/main.rs"},"region":{"startLine":1,"startColumn":1,"endLine":1,"endColumn":4}}}]}"#
+    );
+    assert_eq!(Loc::DUMMY.to_sarif_region(), None);
+    assert_eq!(Loc::new(pos(1, 2), Pos::DUMMY).to_sarif_region(), None);
+    let result = Loc::DUMMY.to_sarif_result("src/main.rs", "E001", "bad");
+    assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        r#"{"ruleId":"E001","message":{"text":"bad"},"locations":[{"physicalLocation":{"artifactLocation":{"uri":"src/main.rs"}}}]}"#
+    );
+}
+
+#[test]
+fn test_center() {
+    assert_eq!(locof!(1, 2, 1, 6).center(), Some(pos(1, 4)));
+    assert_eq!(locof!(1, 2, 1, 5).center(), Some(pos(1, 3)));
+    assert_eq!(locof!(1, 2, 1, 2).center(), Some(pos(1, 2)));
+    assert_eq!(locof!(1, 2, 2, 0).center(), None);
+    assert_eq!(
+        Loc::new_at(0, usize::MAX, 0, usize::MAX).center(),
+        Some(pos(0, usize::MAX))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_annotation_command() {
+    assert_eq!(
+        annotation_command(
+            AnnotationLevel::Error,
+            "src/x.rs",
+            locof!(2, 6, 2, 9),
+            "bad"
+        ),
+        "::error file=src/x.rs,line=3,col=7,endColumn=9::bad"
+    );
+    assert_eq!(
+        annotation_command(
+            AnnotationLevel::Warning,
+            "src/x.rs",
+            locof!(2, 6, 4, 1),
+            "bad"
+        ),
+        "::warning file=src/x.rs,line=3,endLine=5::bad"
+    );
+    assert_eq!(
+        annotation_command(
+            AnnotationLevel::Warning,
+            "src/x.rs",
+            locof!(2, 6, 5, 0),
+            "bad"
+        ),
+        "::warning file=src/x.rs,line=3,endLine=5::bad"
+    );
+    assert_eq!(
+        annotation_command(
+            AnnotationLevel::Notice,
+            "a,b:c.rs",
+            locof!(0, 0, 0, 0),
+            "100%\r\nsure"
+        ),
+        "::notice file=a%2Cb%3Ac.rs,line=1,col=1::100%25%0D%0Asure"
+    );
+    assert_eq!(
+        annotation_command(AnnotationLevel::Error, "src/x.rs", Loc::DUMMY, "bad"),
+        "::error file=src/x.rs::bad"
+    );
+    assert_eq!(
+        annotation_command(
+            AnnotationLevel::Error,
+            "src/x.rs",
+            Loc::new(pos(2, 6), Pos::DUMMY),
+            "bad"
+        ),
+        "::error file=src/x.rs,line=3,col=7::bad"
+    );
+}
+
+#[test]
+fn test_byte_column() {
+    let line = "let é = 1;";
+    let p = Pos::from_byte_col(3, 7);
+    assert_eq!(p, ByteColumn(pos(3, 7)));
+    assert_eq!(p.to_char_col(line), Some(pos(3, 6)));
+    assert_eq!(Pos::from_byte_col(3, 5).to_char_col(line), None);
+    assert_eq!(Pos::from_byte_col(3, 12).to_char_col(line), None);
+    assert_eq!(
+        Pos::from_byte_col(3, 11).to_char_col(line),
+        Some(pos(3, 10))
+    );
+    assert_eq!(pos(3, 6).to_byte_col(line), Some(p));
+    assert_eq!(pos(3, 10).to_byte_col(line), Some(ByteColumn(pos(3, 11))));
+    assert_eq!(pos(3, 11).to_byte_col(line), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_quickfix() {
+    assert_eq!(
+        quickfix_line("src/a.rs", pos(2, 6), "bad\nthing\r\nhere"),
+        "src/a.rs:3:7: bad thing here"
+    );
+    assert_eq!(
+        quickfix_line_loc("src/a.rs", locof!(0, 0, 4, 4), "bad"),
+        "src/a.rs:1:1: bad"
+    );
+    let mut out = String::new();
+    write_quickfix(
+        &mut out,
+        vec![
+            ("b.rs", pos(0, 0), "b"),
+            ("a.rs", pos(3, 1), "a2"),
+            ("a.rs", pos(1, 9), "a1"),
+        ],
+    )
+    .unwrap();
+    assert_eq!(out, "a.rs:2:10: a1\na.rs:4:2: a2\nb.rs:1:1: b\n");
+}
+
+#[test]
+fn test_eq_unordered() {
+    let a = locof!(1, 2, 3, 4);
+    let b = locof!(3, 4, 1, 2);
+    assert_ne!(a, b);
+    assert!(a.eq_unordered(&b));
+    assert!(b.eq_unordered(&a));
+    assert!(b.eq_unordered(&b));
+    assert!(!a.eq_unordered(&locof!(3, 4, 1, 3)));
+}
+
+#[test]
+fn test_fingerprint() {
+    assert_eq!(locof!(1, 2, 3, 4).fingerprint(), 0x898f_7e1c_e696_4921);
+    assert_eq!(Loc::zero().fingerprint(), 0x0c82_1078_4d8a_f5a5);
+    assert_eq!(pos(1, 2).fingerprint(), 0x7717_9803_63c8_e066);
+    assert_ne!(
+        locof!(1, 2, 3, 4).fingerprint(),
+        locof!(3, 4, 1, 2).fingerprint()
+    );
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[test]
+fn test_diagnostic_json() {
+    let d = Diagnostic::new(Severity::Error, "bad", "src/main.rs", locof!(1, 2, 3, 4))
+        .with_code("E001")
+        .with_label("src/lib.rs", locof!(0, 0, 0, 1), "here");
+    let json = serde_json::to_string(&d).unwrap();
+    assert_eq!(
+        json,
+        r#"{"version":1,"severity":"error","message":"bad","code":"E001","file":"src/main.rs","loc":{"from":[1,2],"to":[3,4]},"labels":[{"file":"src/lib.rs","loc":{"from":[0,0],"to":[0,1]},"message":"here"}]}"#
+    );
+    assert_eq!(serde_json::from_str::<Diagnostic>(&json).unwrap(), d);
+    #[cfg(feature = "json")]
+    assert_eq!(d.to_json_line(), json);
+
+    let plain = Diagnostic::new(Severity::Note, "a\nb", "a.rs", Loc::zero());
+    let json = serde_json::to_string(&plain).unwrap();
+    assert!(!json.contains("code") && !json.contains('\n'));
+    assert_eq!(serde_json::from_str::<Diagnostic>(&json).unwrap(), plain);
+    assert!(
+        serde_json::from_str::<Diagnostic>(&json.replace(r#""version":1"#, r#""version":2"#))
+            .is_err()
+    );
+}
+
+#[cfg(feature = "lsp")]
+#[test]
+fn test_diagnostic_lsp() {
+    use PositionEncoding::*;
+
+    let main = LineIndex::new("let 🦀 = 1;\nlet x = 🦀;\n");
+    let lib = LineIndex::new("fn crab() {}\n");
+    let resolve = |file: &str| match file {
+        "src/main.rs" => Some((String::from("file:///p/src/main.rs"), &main)),
+        "src/lib.rs" => Some((String::from("file:///p/src/lib.rs"), &lib)),
+        _ => None,
+    };
+    let d = Diagnostic::new(
+        Severity::Warning,
+        "unused",
+        "src/main.rs",
+        locof!(1, 8, 1, 9),
+    )
+    .with_code("W01")
+    .with_label("src/main.rs", locof!(0, 4, 0, 5), "defined here")
+    .with_label("src/lib.rs", locof!(0, 3, 0, 7), "see also");
+    let lsp = d.to_lsp(Utf16, resolve).unwrap();
+    let range = |l0, c0, l1, c1| LspRange {
+        start: LspPosition {
+            line: l0,
+            character: c0,
+        },
+        end: LspPosition {
+            line: l1,
+            character: c1,
+        },
+    };
+    assert_eq!(
+        lsp,
+        LspDiagnostic {
+            range: range(1, 8, 1, 10),
+            severity: 2,
+            code: Some(String::from("W01")),
+            message: String::from("unused"),
+            related_information: vec![
+                LspRelatedInformation {
+                    location: LspLocation {
+                        uri: String::from("file:///p/src/main.rs"),
+                        range: range(0, 4, 0, 6),
+                    },
+                    message: String::from("defined here"),
+                },
+                LspRelatedInformation {
+                    location: LspLocation {
+                        uri: String::from("file:///p/src/lib.rs"),
+                        range: range(0, 3, 0, 7),
+                    },
+                    message: String::from("see also"),
+                },
+            ],
+        }
+    );
+    assert_eq!(
+        serde_json::to_string(&lsp.related_information[1]).unwrap(),
+        r#"{"location":{"uri":"file:///p/src/lib.rs","range":{"start":{"line":0,"character":3},"end":{"line":0,"character":7}}},"message":"see also"}"#
+    );
+    let json = serde_json::to_string(&lsp).unwrap();
+    assert!(json.contains(r#""severity":2,"code":"W01","message":"unused","relatedInformation":["#));
+    assert_eq!(serde_json::from_str::<LspDiagnostic>(&json).unwrap(), lsp);
+
+    assert_eq!(d.to_lsp(Utf8, resolve).unwrap().range, range(1, 8, 1, 12));
+    assert_eq!(
+        Loc::from_lsp_range(range(1, 8, 1, 10), &main, Utf16),
+        Some(locof!(1, 8, 1, 9))
+    );
+    let plain = Diagnostic::new(Severity::Help, "try", "src/lib.rs", locof!(0, 0, 0, 2));
+    let json = serde_json::to_string(&plain.to_lsp(Utf16, resolve).unwrap()).unwrap();
+    assert_eq!(
+        json,
+        r#"{"range":{"start":{"line":0,"character":0},"end":{"line":0,"character":2}},"severity":4,"message":"try"}"#
+    );
+    let unknown = d.clone().with_label("src/other.rs", Loc::zero(), "?");
+    assert_eq!(unknown.to_lsp(Utf16, resolve), None);
+    let outside = Diagnostic::new(Severity::Error, "bad", "src/lib.rs", locof!(3, 0, 3, 1));
+    assert_eq!(outside.to_lsp(Utf16, resolve), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_iter_positions() {
+    let index = LineIndex::new("ab\né\n");
+    let all: Vec<_> = locof!(0, 1, 2, 0).iter_positions(&index).collect();
+    assert_eq!(
+        all,
+        vec![pos(0, 1), pos(0, 2), pos(1, 0), pos(1, 1), pos(2, 0)]
+    );
+    let point: Vec<_> = locof!(1, 1, 1, 1).iter_positions(&index).collect();
+    assert_eq!(point, vec![pos(1, 1)]);
+    assert_eq!(locof!(1, 1, 0, 0).iter_positions(&index).count(), 0);
+    assert_eq!(locof!(5, 0, 6, 0).iter_positions(&index).count(), 0);
+    assert_eq!(locof!(2, 0, 9, 0).iter_positions(&index).count(), 1);
+    let past_end: Vec<_> = locof!(0, 1, 0, 9).iter_positions(&index).collect();
+    assert_eq!(past_end, vec![pos(0, 1), pos(0, 2)]);
+    let past_end: Vec<_> = locof!(0, 2, 1, 5).iter_positions(&index).collect();
+    assert_eq!(past_end, vec![pos(0, 2), pos(1, 0), pos(1, 1)]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_editor_uri() {
+    use std::path::Path;
+
+    let path = Path::new("/home/me/Мои проекты/main.rs");
+    let encoded =
+        "/home/me/%D0%9C%D0%BE%D0%B8%20%D0%BF%D1%80%D0%BE%D0%B5%D0%BA%D1%82%D1%8B/main.rs";
+    assert_eq!(
+        editor_uri(EditorScheme::VsCode, path, pos(11, 4)),
+        format!("vscode://file{}:12:5", encoded)
+    );
+    assert_eq!(
+        editor_uri(EditorScheme::JetBrains, path, pos(11, 4)),
+        format!("idea://open?file={}&line=12&column=5", encoded)
+    );
+    assert_eq!(
+        editor_uri(EditorScheme::File, path, pos(11, 4)),
+        format!("file://{}#L12", encoded)
+    );
+    assert_eq!(
+        editor_uri(EditorScheme::File, Path::new("C:/a b/c.rs"), pos(0, 0)),
+        "file:///C:/a%20b/c.rs#L1"
+    );
+    let path = Path::new("/a.rs");
+    assert_eq!(
+        editor_uri(EditorScheme::VsCode, path, Pos::DUMMY),
+        "vscode://file/a.rs"
+    );
+    assert_eq!(
+        editor_uri(EditorScheme::JetBrains, path, Pos::DUMMY),
+        "idea://open?file=/a.rs"
+    );
+    assert_eq!(
+        editor_uri(EditorScheme::File, path, Pos::DUMMY),
+        "file:///a.rs"
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_position_encoding() {
+    use PositionEncoding::*;
+
+    // `é` is 2 bytes 1 unit, `🦀` 4 bytes 2 units
+    let index = LineIndex::new("aé🦀b\nx");
+    let b = pos(0, 3);
+    assert_eq!(index.encode_column(b, Utf8), Some(7));
+    assert_eq!(index.encode_column(b, Utf16), Some(4));
+    assert_eq!(index.encode_column(b, Utf32), Some(3));
+    assert_eq!(index.decode_column(0, 7, Utf8), Some(b));
+    assert_eq!(index.decode_column(0, 4, Utf16), Some(b));
+    assert_eq!(index.decode_column(0, 3, Utf32), Some(b));
+    assert_eq!(index.decode_column(0, 3, Utf16), None);
+    assert_eq!(index.decode_column(0, 2, Utf8), None);
+    assert_eq!(index.decode_column(0, 6, Utf16), None);
+    assert_eq!(index.decode_column(1, 1, Utf16), Some(pos(1, 1)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_semantic_tokens() {
+    use PositionEncoding::*;
+
+    let src = "fn main() {\n    let 🦀 = \"é\";\n    🦀\n}";
+    let index = LineIndex::new(src);
+    let tokens = vec![
+        (locof!(0, 0, 0, 2), 0, 0),
+        (locof!(0, 3, 0, 7), 1, 1),
+        (locof!(1, 4, 1, 7), 0, 0),
+        (locof!(1, 8, 1, 9), 2, 0),
+        (locof!(1, 12, 1, 15), 3, 0),
+        (locof!(2, 4, 2, 5), 2, 0),
+    ];
+    let data = encode_semantic_tokens(&tokens, Utf16, &index).unwrap();
+    assert_eq!(
+        data,
+        vec![
+            0, 0, 2, 0, 0, //
+            0, 3, 4, 1, 1, //
+            1, 4, 3, 0, 0, //
+            0, 4, 2, 2, 0, //
+            0, 5, 3, 3, 0, //
+            1, 4, 2, 2, 0,
+        ]
+    );
+    assert_eq!(
+        decode_semantic_tokens(&data, Utf16, &index),
+        Some(tokens.clone())
+    );
+    let mut shuffled = tokens.clone();
+    shuffled.reverse();
+    assert_eq!(encode_semantic_tokens(&shuffled, Utf16, &index), Ok(data));
+    for encoding in [Utf8, Utf32] {
+        let data = encode_semantic_tokens(&tokens, encoding, &index).unwrap();
+        assert_eq!(
+            decode_semantic_tokens(&data, encoding, &index),
+            Some(tokens.clone())
+        );
+    }
+    let multi = locof!(0, 0, 1, 0);
+    assert_eq!(
+        encode_semantic_tokens(&[(multi, 0, 0)], Utf16, &index),
+        Err(SemanticTokenError::MultiLine(multi))
+    );
+    assert_eq!(
+        encode_semantic_tokens(&[(locof!(0, 5, 0, 2), 4, 0)], Utf16, &index),
+        Ok(vec![0, 2, 3, 4, 0])
+    );
+}
+
+#[test]
+fn test_is_adjacent_to() {
+    let a = locof!(1, 2, 1, 5);
+    let b = locof!(1, 5, 2, 0);
+    assert!(a.is_adjacent_to(&b));
+    assert!(b.is_adjacent_to(&a));
+    assert!(!a.is_adjacent_to(&locof!(1, 6, 1, 8)));
+    assert!(!a.is_adjacent_to(&locof!(1, 4, 1, 8)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_delta_stream() {
+    let mut seed = 0x2545_f491_4f6c_dd1du64;
+    let mut next = |n: u64| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((seed >> 33) % n) as usize
+    };
+    let mut locs = Vec::new();
+    let mut at = pos(0, 0);
+    for _ in 0..100_000 {
+        at = if next(4) == 0 {
+            pos(at.line + 1 + next(3), next(40))
+        } else {
+            at + next(12)
+        };
+        let to = if next(20) == 0 {
+            pos(at.line + 1 + next(5), next(80))
+        } else {
+            at + next(16)
+        };
+        locs.push(Loc::new(at, to));
+    }
+    let bytes = delta::encode_all(&locs);
+    assert!(bytes.len() < locs.len() * 6, "{} bytes", bytes.len());
+    let back: Vec<Loc> = delta::decode(&bytes).collect::<Result<_, _>>().unwrap();
+    assert_eq!(back, locs);
+
+    let odd = [
+        locof!(5, 5, 1, 1),
+        Loc::DUMMY,
+        Loc::zero(),
+        Loc::new_at(usize::MAX, 0, 0, usize::MAX),
+    ];
+    let bytes = delta::encode_all(&odd);
+    let back: Vec<Loc> = delta::decode(&bytes).collect::<Result<_, _>>().unwrap();
+    assert_eq!(back, odd);
+
+    let mut it = delta::decode(&bytes[..bytes.len() - 1]);
+    assert_eq!(it.by_ref().filter(Result::is_ok).count(), 3);
+    let mut it = delta::decode(&bytes[..bytes.len() - 1]).skip(3);
+    assert_eq!(it.next(), Some(Err(delta::DeltaDecodeError::Truncated)));
+    assert_eq!(it.next(), None);
+    let bad = [0xffu8; 11];
+    assert_eq!(
+        delta::decode(&bad).next(),
+        Some(Err(delta::DeltaDecodeError::Overflow))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_source_text() {
+    let source = SourceText::new("fn é() {\n    1\n}");
+    assert_eq!(source.text_of(locof!(0, 3, 0, 4)), Some("é"));
+    assert_eq!(source.text_of(locof!(0, 7, 1, 5)), Some("{\n    1"));
+    assert_eq!(source.text_of(locof!(1, 2, 1, 2)), Some(""));
+    assert_eq!(source.text_of(locof!(2, 1, 2, 1)), Some(""));
+    assert_eq!(source.text_of(locof!(0, 0, 2, 1)), Some(source.text()));
+    assert_eq!(source.text_of(locof!(1, 5, 0, 0)), None);
+    assert_eq!(source.text_of(locof!(2, 0, 2, 2)), None);
+    assert_eq!(source.line_text(1), Some("    1"));
+    assert_eq!(source.index().line_count(), 3);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_loc_set_lcov() {
+    let set: LocSet = vec![
+        locof!(0, 0, 0, 4),
+        locof!(0, 2, 2, 0),
+        locof!(3, 1, 3, 1),
+        locof!(3, 2, 4, 3),
+        locof!(4, 0, 4, 1),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(set.locs(), &[locof!(0, 0, 2, 0), locof!(3, 2, 4, 3)]);
+    assert!(set.contains(pos(1, 9)));
+    assert!(!set.contains(pos(2, 0)));
+    assert!(!set.contains(pos(3, 1)));
+    assert_eq!(set.covered_lines().collect::<Vec<_>>(), vec![0, 1, 3, 4]);
+    assert_eq!(set.coverage_of(6), 4.0 / 6.0);
+    assert_eq!(set.coverage_of(2), 1.0);
+    assert_eq!(LocSet::new().coverage_of(0), 0.0);
+
+    let mut out = String::new();
+    write_lcov(&mut out, "src/main.lox", &set, 6).unwrap();
+    assert_eq!(out, include_str!("../tests/golden/coverage.lcov"));
+
+    let mut built = LocSet::new();
+    for l in set.locs().iter().rev() {
+        built.insert(*l);
+    }
+    built.insert(locof!(4, 3, 4, 3));
+    assert_eq!(built, set);
+    built.insert(locof!(2, 0, 3, 2));
+    assert_eq!(built.locs(), &[locof!(0, 0, 4, 3)]);
+    built.insert(locof!(6, 0, 6, 1));
+    built.insert(locof!(5, 1, 5, 2));
+    built.insert(locof!(5, 0, 5, 1));
+    assert_eq!(
+        built.locs(),
+        &[locof!(0, 0, 4, 3), locof!(5, 0, 5, 2), locof!(6, 0, 6, 1)]
+    );
+    built.insert(locof!(4, 3, 6, 0));
+    assert_eq!(built.locs(), &[locof!(0, 0, 6, 1)]);
+
+    let mut open = set.clone();
+    open.insert(Loc::new(pos(5, 2), Pos::DUMMY));
+    assert_eq!(open.covered_lines().collect::<Vec<_>>(), vec![0, 1, 3, 4]);
+    assert_eq!(open.coverage_of(6), 4.0 / 6.0);
+    let mut out = String::new();
+    write_lcov(&mut out, "src/main.lox", &open, 6).unwrap();
+    assert_eq!(out, include_str!("../tests/golden/coverage.lcov"));
+}
+
+#[test]
+fn test_by_start() {
+    use std::collections::BinaryHeap;
+
+    let mut heap = BinaryHeap::new();
+    for l in [
+        locof!(2, 0, 2, 5),
+        locof!(0, 4, 0, 6),
+        locof!(2, 0, 2, 1),
+        locof!(0, 1, 3, 0),
+    ] {
+        heap.push(ByStart(l));
+    }
+    let order: Vec<Loc> = std::iter::from_fn(|| heap.pop().map(|b| b.0)).collect();
+    assert_eq!(
+        order,
+        vec![
+            locof!(0, 1, 3, 0),
+            locof!(0, 4, 0, 6),
+            locof!(2, 0, 2, 1),
+            locof!(2, 0, 2, 5)
+        ]
+    );
+    assert!(ByStart(locof!(0, 0, 0, 1)) > ByStart(locof!(0, 1, 0, 1)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_diff_remapper() {
+    let remap = DiffRemapper::parse(include_str!("../tests/golden/change.diff")).unwrap();
+    assert_eq!(
+        remap.paths().collect::<Vec<_>>(),
+        ["notes.txt", "src/main.rs"]
+    );
+
+    let notes = remap.file("notes.txt");
+    assert_eq!(notes.remap_line(2), LineMapping::Same(2));
+    assert_eq!(notes.remap_line(0), LineMapping::Same(0));
+
+    let main = remap.file("src/main.rs");
+    assert_eq!(main.remap_line(0), LineMapping::Moved(2));
+    assert_eq!(main.remap_line(3), LineMapping::Moved(5));
+    assert_eq!(main.remap_line(8), LineMapping::Removed);
+    assert_eq!(main.remap_line(10), LineMapping::Removed);
+    assert_eq!(main.remap_line(20), LineMapping::Moved(21));
+    assert_eq!(
+        main.remap_loc(locof!(7, 8, 9, 9)),
+        Some(locof!(9, 8, 10, 9))
+    );
+    assert_eq!(main.remap_loc(locof!(7, 8, 8, 9)), None);
+    assert_eq!(main.remap_loc(Loc::DUMMY), Some(Loc::DUMMY));
+
+    let old: Vec<&str> = include_str!("../tests/golden/change_old.txt")
+        .lines()
+        .collect();
+    let new: Vec<&str> = include_str!("../tests/golden/change_new.txt")
+        .lines()
+        .collect();
+    let mut removed = 0;
+    for (line, text) in old.iter().enumerate() {
+        match main.remap_line(line).line() {
+            Some(to) => assert_eq!(new[to], *text, "old line {}", line),
+            None => removed += 1,
+        }
+    }
+    assert_eq!(removed, 2);
+
+    let untouched = remap.file("README.md");
+    assert_eq!(untouched.remap_line(4), LineMapping::Same(4));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_diff_remapper_errors() {
+    assert_eq!(
+        DiffRemapper::parse("@@ -1 +1 @@\n-a\n+b\n"),
+        Err(DiffParseError::HunkOutsideFile { line: 1 })
+    );
+    assert_eq!(
+        DiffRemapper::parse("--- a/x\n+++ b/x\n@@ -1 +x @@\n"),
+        Err(DiffParseError::BadHunkHeader { line: 3 })
+    );
+    assert_eq!(
+        DiffRemapper::parse("--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n a\n+b\n"),
+        Err(DiffParseError::ShortHunk { line: 3 })
+    );
+    assert_eq!(
+        DiffRemapper::parse("--- a/x\n+++ b/x\n@@ -1,2 +1,2 @@\n a\ndiff --git a/y b/y\n"),
+        Err(DiffParseError::ShortHunk { line: 5 })
+    );
+    let added = DiffRemapper::parse(
+        "--- /dev/null\n+++ b/new.rs\n@@ -0,0 +1 @@\n+fn a() {}\n\\ No newline at end of file\n",
+    )
+    .unwrap();
+    assert_eq!(added.paths().collect::<Vec<_>>(), ["new.rs"]);
+}
+
+#[test]
+fn test_small() {
+    use core::convert::TryFrom;
+
+    let max = u32::MAX as usize;
+    let p = SmallPos::try_from(pos(max - 1, max)).unwrap();
+    assert_eq!(p, SmallPos::new(u32::MAX - 1, u32::MAX));
+    assert_eq!(Pos::from(p), pos(max - 1, max));
+    assert!(SmallPos::try_from(pos(max + 1, 0)).is_err());
+    assert!(SmallPos::try_from(pos(0, max + 1)).is_err());
+    assert_eq!(SmallPos::try_from(Pos::DUMMY), Ok(SmallPos::DUMMY));
+    assert_eq!(Pos::from(SmallPos::DUMMY), Pos::DUMMY);
+
+    let l = locof!(1, 2, max, 4);
+    let small = SmallLoc::try_from(l).unwrap();
+    assert_eq!(small.widen(), l);
+    assert_eq!(small, l);
+    assert!(SmallLoc::try_from(locof!(1, 2, max + 1, 4)).is_err());
+    assert_eq!(Loc::from(SmallLoc::DUMMY), Loc::DUMMY);
+
+    assert_eq!(small.to_string(), l.to_string());
+    assert_eq!(SmallPos::DUMMY.to_string(), Pos::DUMMY.to_string());
+    assert_eq!(SmallLoc::default(), SmallLoc::zero());
+    assert!(small.contains(SmallPos::new(1, 3)));
+}
+
+#[test]
+fn test_small_matches_wide() {
+    let a = SmallLoc::new_at(1, 2, 3, 4);
+    let b = SmallLoc::new_at(2, 0, 5, 1);
+    let reversed = SmallLoc::new_at(3, 4, 1, 2);
+    assert_eq!(a.merge(&b), a.widen().merge(&b.widen()));
+    assert_eq!(a | b, SmallLoc::new_at(1, 2, 5, 1));
+    assert_eq!(a.intersection(&b), Some(SmallLoc::new_at(2, 0, 3, 4)));
+    assert_eq!(
+        a & SmallLoc::new_at(7, 0, 8, 0),
+        SmallLoc::new_at(3, 4, 3, 4)
+    );
+    assert_eq!(reversed.normalize(), a);
+    assert!(reversed.eq_unordered(&a));
+    assert!(a.merge(&b).contains_loc(&b));
+    assert_eq!(a.lines(), 1..=3);
+    assert_eq!(
+        SmallLoc::new(SmallPos::new(1, 2), SmallPos::DUMMY)
+            .lines()
+            .count(),
+        0
+    );
+    assert_eq!(a.line_range(), 1..4);
+    assert_eq!(
+        a.rebase(SmallPos::new(10, 0)),
+        SmallLoc::new_at(11, 2, 13, 4)
+    );
+    assert_eq!(
+        a.rebase(SmallPos::new(10, 0))
+            .relative_within(&SmallLoc::new_at(10, 0, 20, 0)),
+        Some(a)
+    );
+    assert_eq!(SmallPos::new(0, 5).clamp_to(a), a.from);
+    assert_eq!(SmallPos::new(1, u32::MAX) + 1, SmallPos::new(1, u32::MAX));
+    assert_eq!(a - 3, SmallLoc::new_at(1, 0, 3, 1));
+    assert!(SmallLoc::DUMMY.is_dummy() && !SmallLoc::zero().is_dummy());
+    assert_eq!(SmallLoc::default(), SmallLoc::zero());
+}
+
+#[cfg(feature = "compact-debug")]
+#[test]
+fn test_small_debug() {
+    assert_eq!(format!("{:?}", SmallPos::new(1, 2)), "SmallPos(1:2)");
+    assert_eq!(
+        format!("{:?}", SmallLoc::new_at(1, 2, 3, 4)),
+        "SmallLoc(1:2..3:4)"
+    );
+    assert_eq!(format!("{:?}", SmallPos::DUMMY), "SmallPos(<unknown>)");
+    assert_eq!(
+        format!("{:#?}", SmallPos::new(1, 2)),
+        "SmallPos {\n    line: 1,\n    column: 2,\n}"
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_small_serde() {
+    let l = SmallLoc::new_at(1, 2, 3, 4);
+    let json = serde_json::to_string(&l).unwrap();
+    assert_eq!(json, serde_json::to_string(&locof!(1, 2, 3, 4)).unwrap());
+    assert_eq!(serde_json::from_str::<SmallLoc>(&json).unwrap(), l);
+    assert!(serde_json::from_str::<SmallPos>(r#"{"line":4294967296,"column":0}"#).is_err());
+}
+
+#[test]
+fn test_opt() {
+    let p = OptPos::from(pos(1, 2));
+    assert!(p.is_some());
+    assert_eq!(Option::<Pos>::from(p), Some(pos(1, 2)));
+    assert_eq!(OptPos::from(None::<Pos>), OptPos::NONE);
+    assert_eq!(OptPos::from(Some(Pos::DUMMY)), OptPos::NONE);
+    assert_eq!(OptPos::NONE.unwrap_or_dummy(), Pos::DUMMY);
+    assert_eq!(OptLoc::default().get(), None);
+
+    let mut all = [
+        OptLoc::from(locof!(2, 0, 2, 1)),
+        OptLoc::NONE,
+        OptLoc::from(locof!(0, 0, 0, 1)),
+    ];
+    all.sort();
+    assert_eq!(
+        all.iter().map(|l| l.get()).collect::<Vec<_>>(),
+        vec![None, Some(locof!(0, 0, 0, 1)), Some(locof!(2, 0, 2, 1))]
+    );
+    let mut taken = all[1];
+    assert_eq!(taken.take(), Some(locof!(0, 0, 0, 1)));
+    assert!(taken.is_none());
+    assert_eq!(format!("{:?}", OptPos::NONE), "None");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_opt_serde() {
+    assert_eq!(serde_json::to_string(&OptLoc::NONE).unwrap(), "null");
+    let l = OptLoc::from(locof!(1, 2, 3, 4));
+    let json = serde_json::to_string(&l).unwrap();
+    assert_eq!(json, serde_json::to_string(&locof!(1, 2, 3, 4)).unwrap());
+    assert_eq!(serde_json::from_str::<OptLoc>(&json).unwrap(), l);
+    assert_eq!(
+        serde_json::from_str::<OptPos>("null").unwrap(),
+        OptPos::NONE
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_per_line_counts() {
+    assert_eq!(locof!(2, 4, 4, 0).lines(), 2..=4);
+    assert!(Loc::DUMMY.lines().is_empty());
+    assert_eq!(locof!(2, 4, 4, 0).line_range(), 2..5);
+    assert!(Loc::DUMMY.line_range().is_empty());
+    let open = Loc::new(pos(2, 0), Pos::DUMMY);
+    assert!(open.lines().is_empty());
+    assert!(open.line_range().is_empty());
+    let src = ["a", "b", "c", "d"];
+    assert_eq!(
+        src.get(locof!(1, 0, 2, 1).line_range()),
+        Some(&["b", "c"][..])
+    );
+    const RANGE: Range<usize> = locof!(0, 0, 0, 1).line_range();
+    assert_eq!(RANGE, 0..1);
+    let counts = Loc::per_line_counts(&[
+        locof!(0, 0, 0, 3),
+        locof!(0, 5, 2, 1),
+        locof!(2, 0, 2, 0),
+        Loc::DUMMY,
+        open,
+    ]);
+    assert_eq!(
+        counts.into_iter().collect::<Vec<_>>(),
+        vec![(0, 2), (1, 1), (2, 2)]
+    );
+    assert!(Loc::per_line_counts(&[]).is_empty());
+}
+
+#[cfg(feature = "fast")]
+#[test]
+fn test_line_index_fast_matches_scalar() {
+    const PIECES: [&str; 8] = [
+        "a",
+        "bc",
+        " ",
+        "\n",
+        "\r\n",
+        "\u{e9}",
+        "\u{4e2d}",
+        "\u{1f980}",
+    ];
+    let mut state = 0x2545_f491_4f6c_dd1du64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    for _ in 0..500 {
+        let len = (next() % 64) as usize;
+        let text: String = (0..len)
+            .map(|_| PIECES[(next() % PIECES.len() as u64) as usize])
+            .collect();
+        assert_eq!(
+            LineIndex::new(&text),
+            LineIndex::new_scalar(&text),
+            "{:?}",
+            text
+        );
+    }
+    for text in ["", "\n", "a\r\nb", "no newline", "\u{1f980}\n\u{e9}"] {
+        assert_eq!(
+            LineIndex::new(text),
+            LineIndex::new_scalar(text),
+            "{:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_relation_to() {
+    use SpanRelation::*;
+
+    let a = locof!(1, 0, 1, 8);
+    let cases = [
+        (locof!(1, 0, 1, 8), Equal),
+        (locof!(1, 2, 1, 4), Contains),
+        (locof!(1, 0, 1, 0), Contains),
+        (locof!(1, 8, 1, 8), Contains),
+        (locof!(0, 0, 2, 0), ContainedBy),
+        (locof!(1, 8, 2, 0), Adjacent),
+        (locof!(0, 3, 1, 0), Adjacent),
+        (locof!(1, 6, 1, 9), Overlaps),
+        (locof!(0, 0, 1, 1), Overlaps),
+        (locof!(2, 0, 2, 1), Before),
+        (locof!(0, 0, 0, 9), After),
+    ];
+    for &(b, relation) in cases.iter() {
+        assert_eq!(a.relation_to(&b), relation, "{:?}", b);
+        assert_eq!(b.relation_to(&a), relation.flip(), "{:?}", b);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_line_index_cursor() {
+    let text = "a\u{e9}b\n\n\u{1f980}\u{4e2d}x\r\nlast \u{e9}";
+    let index = LineIndex::new(text);
+    let mut cursor = index.cursor();
+    for offset in 0..=text.len() + 1 {
+        assert_eq!(
+            cursor.pos_of(offset),
+            index.pos_of(offset),
+            "sorted {}",
+            offset
+        );
+    }
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    let mut cursor = index.cursor();
+    for _ in 0..1000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let offset = (state % (text.len() as u64 + 2)) as usize;
+        assert_eq!(
+            cursor.pos_of(offset),
+            index.pos_of(offset),
+            "random {}",
+            offset
+        );
+    }
+}
+
+#[test]
+fn test_compact_loc() {
+    let (line, column, delta) = (
+        CompactLoc::MAX_LINE,
+        CompactLoc::MAX_COLUMN,
+        CompactLoc::MAX_LINE_DELTA,
+    );
+    assert_eq!((line, column, delta), (0xffffe, 0xfff, 0xfffff));
+    for &l in [
+        locof!(0, 0, 0, 0),
+        locof!(line, 0, line, 0),
+        locof!(0, column, 0, column),
+        locof!(0, 0, delta, 0),
+        locof!(line, column, line + delta, column),
+    ]
+    .iter()
+    {
+        let packed = CompactLoc::try_pack(l).unwrap();
+        assert!(!packed.is_overflow());
+        assert_eq!(packed.unpack(), l);
+        assert_eq!(CompactLoc::from_bits(packed.to_bits()), packed);
+    }
+    for &l in [
+        locof!(line + 1, 0, line + 1, 0),
+        locof!(0, column + 1, 0, 0),
+        locof!(0, 0, 0, column + 1),
+        locof!(0, 0, delta + 1, 0),
+        locof!(2, 0, 1, 0),
+        Loc::DUMMY,
+    ]
+    .iter()
+    {
+        assert_eq!(CompactLoc::try_pack(l), None, "{:?}", l);
+    }
+    assert_eq!(
+        CompactLoc::try_pack(locof!(1, 2, 3, 4)).unwrap().to_bits(),
+        1 << 44 | 2 << 32 | 2 << 12 | 4
+    );
+    assert!(CompactLoc::from_bits(u64::MAX).is_overflow());
+    assert_eq!(CompactLoc::from_bits(u64::MAX).unpack(), Loc::DUMMY);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_compact_loc_table() {
+    let locs = [
+        locof!(1, 2, 1, 8),
+        Loc::DUMMY,
+        locof!(0, 0, 2_000_000, 0),
+        locof!(5, 5, 5, 6),
+        locof!(1 << 20, 0, 1 << 20, 1),
+    ];
+    let table: CompactLocTable = locs.iter().copied().collect();
+    assert_eq!(table.len(), 5);
+    assert_eq!(table.overflow_len(), 3);
+    assert_eq!(table.iter().collect::<Vec<_>>(), locs);
+    assert_eq!(table.get(5), None);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_positions_of_sorted() {
+    let text = "fn a() {\n\n    \u{e9}t\u{e9} \u{1f980}\n}\nlast";
+    let index = LineIndex::new(text);
+    let offsets: Vec<usize> = (0..=text.len() + 2).chain([3, 40, 40, 41]).collect();
+    let mut sorted = offsets.clone();
+    sorted.sort_unstable();
+    let expected: Vec<_> = sorted.iter().map(|&o| index.pos_of(o)).collect();
+    assert_eq!(index.positions_of_sorted(&sorted), expected);
+    assert_eq!(
+        index
+            .positions_of_sorted_iter(sorted.iter().copied())
+            .collect::<Vec<_>>(),
+        expected
+    );
+
+    let ranges = [
+        0..2,
+        3..4,
+        3..30,
+        9..10,
+        12..16,
+        12..15,
+        28..text.len(),
+        30..99,
+    ];
+    let locs = index.locs_of_sorted_ranges(&ranges);
+    for (r, l) in ranges.iter().zip(&locs) {
+        let expected = index
+            .pos_of(r.start)
+            .and_then(|from| Some(Loc::new(from, index.pos_of(r.end)?)));
+        assert_eq!(*l, expected, "{:?}", r);
+    }
+    assert_eq!(locs[0], Some(locof!(0, 0, 0, 2)));
+    assert_eq!(locs[7], None);
+}
+
+#[cfg(all(feature = "alloc", debug_assertions))]
+#[test]
+#[should_panic(expected = "offsets are not sorted")]
+fn test_positions_of_sorted_unsorted() {
+    LineIndex::new("ab\ncd").positions_of_sorted(&[3, 1]);
+}
+
+#[test]
+fn test_pos_cursor() {
+    let src = "a\u{e9}\n\n\u{1f980}x\r\ny";
+    let mut cursor = PosCursor::new(src);
+    let forward: Vec<_> = cursor.by_ref().collect();
+    assert_eq!(forward.len(), src.chars().count());
+    for (&(c, p), (offset, expected)) in forward.iter().zip(src.char_indices()) {
+        assert_eq!(c, expected);
+        assert_eq!(Some(p), Pos::from_offset(src, offset));
+    }
+    assert_eq!(cursor.pos(), pos(3, 1));
+    assert_eq!(cursor.offset(), src.len());
+    assert_eq!(cursor.next(), None);
+
+    let mut backward = Vec::new();
+    while let Some(step) = cursor.prev() {
+        backward.push(step);
+    }
+    backward.reverse();
+    assert_eq!(backward, forward);
+    assert_eq!((cursor.pos(), cursor.offset()), (pos(0, 0), 0));
+    assert_eq!(cursor.prev(), None);
+
+    cursor.nth(4);
+    assert_eq!(cursor.peek(), Some('x'));
+    assert_eq!(cursor.pos(), pos(2, 1));
+    assert_eq!(cursor.prev(), Some(('\u{1f980}', pos(2, 0))));
+    assert_eq!(cursor.prev(), Some(('\n', pos(1, 0))));
+    assert_eq!(cursor.prev(), Some(('\n', pos(0, 2))));
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_line_index_parallel() {
+    const PIECES: [&str; 6] = ["abcdefg", "\n", "\r\n", "\u{e9}", "\u{4e2d}", "\u{1f980}"];
+    let mut state = 0x853c_49e6_748f_ea9bu64;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    // four threads so the text splits into chunks even on one core
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(4)
+        .build()
+        .unwrap();
+    for &len in [0, 1, 100, 1 << 14, 1 << 16, 3 << 16].iter() {
+        let text: String = (0..len)
+            .map(|_| PIECES[(next() % PIECES.len() as u64) as usize])
+            .collect();
+        let parallel = pool.install(|| LineIndex::new_parallel(&text));
+        assert_eq!(parallel, LineIndex::new(&text), "{}", len);
+        assert_eq!(
+            LineIndex::new_parallel(&text),
+            LineIndex::new(&text),
+            "{}",
+            len
+        );
+    }
+    let crlf = "x\r\n".repeat(1 << 17);
+    let parallel = pool.install(|| LineIndex::new_parallel(&crlf));
+    assert_eq!(parallel, LineIndex::new(&crlf));
+
+    let files: Vec<String> = (0..40)
+        .map(|i| {
+            (0..i * 997)
+                .map(|_| PIECES[(next() % PIECES.len() as u64) as usize])
+                .collect()
+        })
+        .chain(Some(crlf))
+        .collect();
+    let all = pool.install(|| LineIndex::index_all_parallel(&files));
+    assert_eq!(
+        all,
+        files.iter().map(|t| LineIndex::new(t)).collect::<Vec<_>>()
+    );
+    assert!(LineIndex::index_all_parallel::<&str>(&[]).is_empty());
+}
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[test]
+fn test_serde_round_trip_all_forms() {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Forms {
+        derived: Loc,
+        #[serde(with = "crate::serde_compact")]
+        array: Loc,
+        #[serde(with = "crate::serde_any")]
+        any: Loc,
+        #[serde(with = "crate::serde_strict")]
+        strict: Loc,
+        #[serde(with = "crate::spanned_flat")]
+        flat: Spanned<std::collections::BTreeMap<String, u8>>,
+        small: SmallLoc,
+        opt: OptLoc,
+        packed: u64,
+    }
+
+    let locs = [
+        locof!(1, 2, 3, 4),
+        Loc::zero(),
+        locof!(0, 7, 0, 7),
+        locof!(4095, 4095, 4095, 4095),
+        Loc::DUMMY,
+    ];
+    let mut back = Vec::new();
+    for &l in locs.iter() {
+        let small = core::convert::TryFrom::try_from(l).unwrap();
+        let packed = CompactLoc::try_pack(l).map_or(u64::MAX, CompactLoc::to_bits);
+        let forms = Forms {
+            derived: l,
+            array: l,
+            any: l,
+            strict: l,
+            flat: Spanned::new(std::collections::BTreeMap::new(), l),
+            small,
+            opt: OptLoc::some(l),
+            packed,
+        };
+        let json = serde_json::to_string(&forms).unwrap();
+        let de: Forms = serde_json::from_str(&json).unwrap();
+        assert_eq!(de, forms, "{}", json);
+        for got in [
+            de.derived,
+            de.array,
+            de.any,
+            de.strict,
+            de.flat.loc,
+            Loc::from(de.small),
+        ]
+        .iter()
+        {
+            assert_eq!(*got, l, "{}", json);
+        }
+        if !l.is_dummy() {
+            assert_eq!(de.opt.get(), Some(l));
+            assert_eq!(CompactLoc::from_bits(de.packed).unpack(), l);
+        }
+        back.push(de.array);
+    }
+    assert_eq!(
+        locof!(1, 2, 3, 4),
+        serde_json::from_str::<Loc>(r#"{"from":{"line":1,"column":2},"to":{"line":3,"column":4}}"#)
+            .unwrap()
+    );
+    for json in ["[1,2,3,4]", "[[1,2],[3,4]]"].iter() {
+        let mut de = serde_json::Deserializer::from_str(json);
+        assert_eq!(
+            crate::serde_compact::deserialize::<Loc, _>(&mut de).unwrap(),
+            locof!(1, 2, 3, 4)
+        );
+    }
+    let mut sorted = locs;
+    sorted.sort();
+    back.sort();
+    assert_eq!(back, sorted);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_line_index_find() {
+    let src = "let \u{e9}t\u{e9} = 1;\nlet x = \u{e9}t\u{e9};\n\u{e9}t\u{e9}t\u{e9}";
+    let index = LineIndex::new(src);
+    assert_eq!(index.find(src, "\u{e9}t\u{e9}"), Some(locof!(0, 4, 0, 7)));
+    assert_eq!(index.find(src, "x = "), Some(locof!(1, 4, 1, 8)));
+    assert_eq!(index.find(src, ";\nlet"), Some(locof!(0, 11, 1, 3)));
+    assert_eq!(index.find(src, "nope"), None);
+    assert_eq!(
+        index.find_all(src, "\u{e9}t\u{e9}").collect::<Vec<_>>(),
+        vec![locof!(0, 4, 0, 7), locof!(1, 8, 1, 11), locof!(2, 0, 2, 3)]
+    );
+    assert_eq!(index.find_all(src, "let").count(), 2);
+    assert_eq!(index.find_all(src, "zzz").count(), 0);
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn test_bytemuck() {
+    let locs = vec![locof!(1, 2, 3, 4), Loc::DUMMY, Loc::zero()];
+    let bytes: &[u8] = bytemuck::cast_slice(&locs);
+    assert_eq!(bytes.len(), 3 * core::mem::size_of::<Loc>());
+    assert_eq!(
+        &bytes[..core::mem::size_of::<usize>()],
+        &1usize.to_ne_bytes()
+    );
+    let back: &[Loc] = bytemuck::cast_slice(bytes);
+    assert_eq!(back, locs);
+    assert_eq!(bytemuck::cast_slice::<Pos, usize>(&[pos(5, 6)]), [5, 6]);
+
+    let small = [SmallLoc::new_at(1, 2, 3, 4)];
+    let bytes: &[u8] = bytemuck::cast_slice(&small);
+    assert_eq!(bytes.len(), 16);
+    assert_eq!(bytemuck::pod_read_unaligned::<SmallLoc>(bytes), small[0]);
+
+    assert_eq!(<Loc as bytemuck::Zeroable>::zeroed(), Loc::zero());
+    assert_eq!(<SmallPos as bytemuck::Zeroable>::zeroed(), SmallPos::zero());
+}
+
+#[cfg(feature = "bytemuck")]
+#[test]
+fn test_bytemuck_layout() {
+    use core::mem::{size_of, MaybeUninit};
+    use core::ptr::addr_of;
+    macro_rules! offsets {
+        ($t:ty, $a:ident, $b:ident) => {{
+            let value = MaybeUninit::<$t>::uninit();
+            let base = value.as_ptr();
+            // SAFETY: only field addresses are taken, nothing is read
+            unsafe {
+                (
+                    addr_of!((*base).$a) as usize - base as usize,
+                    addr_of!((*base).$b) as usize - base as usize,
+                )
+            }
+        }};
+    }
+    assert_eq!(offsets!(Pos, line, column), (0, size_of::<usize>()));
+    assert_eq!(offsets!(Loc, from, to), (0, size_of::<Pos>()));
+    assert_eq!(offsets!(SmallPos, line, column), (0, 4));
+    assert_eq!(offsets!(SmallLoc, from, to), (0, 8));
+}
+
+#[test]
+fn test_le_bytes() {
+    const P: [u8; 16] = pos(0x0102, 0x0a0b0c).to_le_bytes();
+    assert_eq!(
+        P,
+        [0x02, 0x01, 0, 0, 0, 0, 0, 0, 0x0c, 0x0b, 0x0a, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(Pos::from_le_bytes(P), pos(0x0102, 0x0a0b0c));
+
+    let l = locof!(1, 2, 3, 4);
+    let bytes = l.to_le_bytes();
+    let mut golden = [0u8; 32];
+    for (i, v) in [1u8, 2, 3, 4].iter().enumerate() {
+        golden[i * 8] = *v;
+    }
+    assert_eq!(bytes, golden);
+    assert_eq!(Loc::from_le_bytes(bytes), l);
+    assert_eq!(Loc::try_from_le_bytes(bytes), Some(l));
+    assert_eq!(Loc::DUMMY.to_le_bytes(), [0xff; 32]);
+    assert_eq!(Loc::try_from_le_bytes([0xff; 32]), Some(Loc::DUMMY));
+    assert_eq!(
+        Loc::try_from_le_bytes(locof!(3, 0, 1, 0).to_le_bytes()),
+        None
+    );
+    assert_eq!(
+        Loc::from_le_bytes(locof!(3, 0, 1, 0).to_le_bytes()),
+        locof!(3, 0, 1, 0)
+    );
+}
+
+#[cfg(feature = "borsh")]
+#[test]
+fn test_le_bytes_match_borsh() {
+    let l = locof!(1, 2, 300, 4);
+    assert_eq!(borsh::to_vec(&l).unwrap(), l.to_le_bytes());
+}
+
+#[cfg(feature = "zerocopy")]
+#[test]
+fn test_zerocopy() {
+    use crate::le_bytes::{LeLoc, LePos};
+    use core::convert::TryFrom;
+    use zerocopy::{FromBytes, IntoBytes};
+
+    let l = locof!(1, 2, 3, 4);
+    let le = LeLoc::from(l);
+    assert_eq!(le.as_bytes(), l.to_le_bytes());
+    assert_eq!(core::mem::align_of::<LeLoc>(), 1);
+
+    // read out of an odd offset of a cache buffer
+    let mut buf = vec![0u8; 1];
+    buf.extend_from_slice(&l.to_le_bytes());
+    buf.extend_from_slice(&locof!(5, 0, 2, 0).to_le_bytes());
+    let (locs, rest) = <[LeLoc]>::ref_from_prefix_with_elems(&buf[1..], 2).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(Loc::try_from(locs[0]), Ok(l));
+    assert_eq!(Loc::try_from(locs[1]), Err(locs[1]));
+    assert_eq!(Pos::from(LePos::from(pos(7, 8))), pos(7, 8));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_span_map() {
+    let mut map: SpanMap<Vec<usize>> = [
+        (vec![], locof!(0, 0, 9, 0)),
+        (vec![0], locof!(1, 0, 3, 1)),
+        (vec![0, 0], locof!(1, 2, 1, 6)),
+        (vec![1], locof!(4, 0, 4, 0)),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    assert_eq!(map.len(), 4);
+    assert_eq!(map.get(&vec![0, 0]), Some(&locof!(1, 2, 1, 6)));
+    let keys = |p| {
+        map.containing(p)
+            .map(|(k, _)| k.clone())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(keys(pos(1, 3)), vec![vec![], vec![0], vec![0, 0]]);
+    assert_eq!(keys(pos(1, 6)), vec![vec![], vec![0]]);
+    assert_eq!(keys(pos(4, 0)), vec![Vec::<usize>::new()]);
+    assert_eq!(keys(pos(9, 0)), Vec::<Vec<usize>>::new());
+
+    assert_eq!(
+        map.insert(vec![1], locof!(4, 0, 4, 2)),
+        Some(locof!(4, 0, 4, 0))
+    );
+    assert_eq!(map.remove(&vec![0]), Some(locof!(1, 0, 3, 1)));
+    assert_eq!(
+        map.iter().map(|(k, _)| k.len()).collect::<Vec<_>>(),
+        vec![0, 2, 1]
+    );
+    assert!(SpanMap::<u8>::default().is_empty());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_offset_pos_round_trip_edges() {
+    for text in [
+        "",
+        "\n",
+        "ab\n",
+        "ab\ncd",
+        "a\r\n\r\nb",
+        "\u{e9}\n\u{1f980}",
+        "x\n\n",
+    ]
+    .iter()
+    {
+        let index = LineIndex::new(text);
+        for (offset, _) in text.char_indices().chain(Some((text.len(), ' '))) {
+            let p = index.pos_of(offset).unwrap();
+            assert_eq!(
+                Pos::from_offset(text, offset),
+                Some(p),
+                "{:?} {}",
+                text,
+                offset
+            );
+            assert_eq!(index.offset_of(p), Some(offset), "{:?} {}", text, offset);
+        }
+        for (offset, c) in text.char_indices().filter(|&(_, c)| c == '\n') {
+            // just before the `\n` is the line end, just after is the next line start
+            let before = index.pos_of(offset).unwrap();
+            assert_eq!(before.column, index.line_len(before.line).unwrap());
+            assert_eq!(
+                index.pos_of(offset + c.len_utf8()),
+                Some(pos(before.line + 1, 0))
+            );
+        }
+        assert_eq!(index.pos_of(text.len()), Some(index.end_pos()));
+        assert_eq!(index.pos_of(text.len() + 1), None);
+    }
+}
@@ -0,0 +1,37 @@
+//! text-size `TextSize`/`TextRange` are `u32` byte offsets,
+//! converting from and to line and column needs a [`LineIndex`]
+
+use crate::{LineIndex, Loc, Pos};
+use core::convert::TryFrom;
+use text_size::{TextRange, TextSize};
+
+impl Pos {
+    /// From a text-size byte offset, `None` if not in the index
+    #[inline]
+    pub fn from_text_size(size: TextSize, index: &LineIndex) -> Option<Pos> {
+        index.pos_of(usize::from(size))
+    }
+
+    /// To a text-size byte offset, `None` if not in the index or out of `u32`
+    pub fn to_text_size(&self, index: &LineIndex) -> Option<TextSize> {
+        let offset = index.offset_of(*self)?;
+        u32::try_from(offset).ok().map(TextSize::from)
+    }
+}
+
+impl Loc {
+    /// From a text-size byte range, `None` if not in the index
+    pub fn from_text_range(range: TextRange, index: &LineIndex) -> Option<Loc> {
+        let from = Pos::from_text_size(range.start(), index)?;
+        let to = Pos::from_text_size(range.end(), index)?;
+        Some(Loc::new(from, to))
+    }
+
+    /// To a text-size byte range, `None` if not in the index or out of `u32`
+    pub fn to_text_range(&self, index: &LineIndex) -> Option<TextRange> {
+        let range = self.byte_range(index)?;
+        let start = u32::try_from(range.start).ok()?;
+        let end = u32::try_from(range.end).ok()?;
+        Some(TextRange::new(start.into(), end.into()))
+    }
+}
@@ -0,0 +1,12 @@
+//! The core api must stay usable from `#![no_std]` crates,  
+//! build with `--no-default-features` to also check the library itself
+#![no_std]
+
+use srcpos::{loc, pos, Loc};
+
+#[test]
+fn test_core_api() {
+    let l: Loc = loc(pos(1, 2), pos(3, 4));
+    assert!(l.contains(pos(2, 0)));
+    assert_eq!(l.merge(&l.end_loc()), l);
+}
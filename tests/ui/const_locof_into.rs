@@ -0,0 +1,8 @@
+use srcpos::*;
+
+// the two argument form goes through `Into`, which is not const
+const L: Loc = locof!(pos(1, 2), pos(3, 4));
+
+fn main() {
+    let _ = L;
+}
@@ -0,0 +1,8 @@
+use srcpos::*;
+
+// the single argument form goes through `From`, which is not const
+const P: Pos = posof!(1);
+
+fn main() {
+    let _ = P;
+}
@@ -0,0 +1,5 @@
+use srcpos::*;
+
+fn main() {
+    let _ = loc_lit!("12:5-");
+}
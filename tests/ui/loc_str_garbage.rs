@@ -0,0 +1,5 @@
+use srcpos::*;
+
+fn main() {
+    let _ = loc_str!("3:7-x");
+}
@@ -0,0 +1,5 @@
+use srcpos::*;
+
+fn main() {
+    let _ = locof!(1, 2, 3);
+}
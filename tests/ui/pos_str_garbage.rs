@@ -0,0 +1,5 @@
+use srcpos::*;
+
+fn main() {
+    let _ = pos_str!("line 3");
+}